@@ -1,24 +1,25 @@
-use crate::cell::GridCell;
-use crate::grid::Grid;
-use rand;
+use rand::Rng;
+
+use crate::topology::Topology;
 pub struct AldousBroder {}
 
 impl AldousBroder {
-    pub fn on(grid: &Grid) {
-        let mut cell_ref = grid.get_random_cell().upgrade().unwrap();
+    pub fn on<T: Topology>(grid: &T) {
+        AldousBroder::on_with_rng(grid, &mut rand::thread_rng());
+    }
+
+    pub fn on_with_rng<T: Topology, R: Rng>(grid: &T, rng: &mut R) {
+        let mut current = grid.random_cell(rng);
         let mut unvisited = grid.size() - 1;
         while unvisited > 0 {
-            let mut neighbours = cell_ref.borrow().neighbours();
-            let rand_num = rand::random::<usize>() % neighbours.len();
-            let rand_neighbour_ref = neighbours.remove(rand_num).upgrade().unwrap();
-            if rand_neighbour_ref.borrow().get_links().is_empty() {
-                GridCell::link(
-                    &mut cell_ref.borrow_mut(),
-                    &mut rand_neighbour_ref.borrow_mut(),
-                );
+            let neighbours = grid.neighbours(&current);
+            let rand_num = rng.gen_range(0, neighbours.len());
+            let neighbour = neighbours[rand_num].clone();
+            if grid.links(&neighbour).is_empty() {
+                grid.link(&current, &neighbour);
                 unvisited -= 1;
             }
-            cell_ref = rand_neighbour_ref;
+            current = neighbour;
         }
     }
 }
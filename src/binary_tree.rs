@@ -1,38 +1,133 @@
-use rand;
+use rand::Rng;
 
-use crate::cell::GridCell;
+use crate::cell::{GridCell, Side};
 use crate::grid::Grid;
 
+#[derive(Debug, Clone, Copy)]
+pub enum Diagonal {
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl Diagonal {
+    pub fn sides(self) -> (Side, Side) {
+        match self {
+            Diagonal::NorthEast => (Side::North, Side::East),
+            Diagonal::NorthWest => (Side::North, Side::West),
+            Diagonal::SouthEast => (Side::South, Side::East),
+            Diagonal::SouthWest => (Side::South, Side::West),
+        }
+    }
+}
+
 pub struct BinaryTree {}
 
 impl BinaryTree {
     pub fn on(grid: &Grid) {
-        for row in 0..grid.rows {
-            for col in 0..grid.rows {
-                let mut neighbours = Vec::new();
+        BinaryTree::on_with_rng(grid, &mut rand::thread_rng());
+    }
 
+    pub fn on_with_rng<R: Rng>(grid: &Grid, rng: &mut R) {
+        BinaryTree::on_biased(grid, Diagonal::NorthEast, 0.5, rng);
+    }
+
+    // The long corridors end up along both sides of the diagonal, and
+    // `vertical_probability` is the chance of carving vertically when both sides are open.
+    pub fn on_biased<R: Rng>(
+        grid: &Grid,
+        diagonal: Diagonal,
+        vertical_probability: f64,
+        rng: &mut R,
+    ) {
+        let (vertical, horizontal) = diagonal.sides();
+        for row in 0..grid.rows {
+            for col in 0..grid.columns {
                 let cell = &grid.grid[row][col];
-                if let Some(cell_ref) = &cell.borrow().north {
-                    neighbours.push(cell_ref.clone());
-                }
-                if let Some(cell_ref) = &cell.borrow().east {
-                    neighbours.push(cell_ref.clone());
-                }
-                match neighbours.len() {
-                    0 => (),
-                    1 => {
-                        let neighbour_cell = neighbours.remove(0).upgrade().unwrap();
-                        GridCell::link(&mut cell.borrow_mut(), &mut neighbour_cell.borrow_mut());
-                    }
-                    2 => {
-                        let rand_index = rand::random::<usize>() % 2;
-                        let neighbour_cell = neighbours.remove(rand_index).upgrade().unwrap();
-                        neighbours.clear();
-                        GridCell::link(&mut cell.borrow_mut(), &mut neighbour_cell.borrow_mut());
+                let vertical_neighbour = cell.borrow().neighbour(vertical);
+                let horizontal_neighbour = cell.borrow().neighbour(horizontal);
+                let neighbour = match (vertical_neighbour, horizontal_neighbour) {
+                    (None, None) => continue,
+                    (Some(neighbour), None) | (None, Some(neighbour)) => neighbour,
+                    (Some(vertical_neighbour), Some(horizontal_neighbour)) => {
+                        if rng.gen::<f64>() < vertical_probability {
+                            vertical_neighbour
+                        } else {
+                            horizontal_neighbour
+                        }
                     }
-                    _ => unreachable!(),
-                }
+                };
+                let neighbour_cell = neighbour.upgrade().unwrap();
+                GridCell::link(&mut cell.borrow_mut(), &mut neighbour_cell.borrow_mut());
             }
         }
+        // Cells cut off from the diagonal by the mask form trees of their own
+        if grid.size() < grid.rows * grid.columns {
+            grid.join_regions(rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Coord;
+    use crate::topology::Topology;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const ROWS: usize = 6;
+    const COLUMNS: usize = 8;
+
+    fn carve(diagonal: Diagonal, vertical_probability: f64) -> Grid {
+        let mut grid = Grid::initialize(ROWS, COLUMNS);
+        grid.configure_cells();
+        let mut rng = StdRng::seed_from_u64(3);
+        BinaryTree::on_biased(&grid, diagonal, vertical_probability, &mut rng);
+        assert!(grid.is_perfect());
+        grid
+    }
+
+    fn row_is_open(grid: &Grid, row: usize) -> bool {
+        (1..COLUMNS)
+            .all(|column| grid.is_linked(&Coord::from(row, column - 1), &Coord::from(row, column)))
+    }
+
+    fn column_is_open(grid: &Grid, column: usize) -> bool {
+        (1..ROWS)
+            .all(|row| grid.is_linked(&Coord::from(row - 1, column), &Coord::from(row, column)))
+    }
+
+    fn edges(diagonal: Diagonal) -> (usize, usize) {
+        match diagonal.sides() {
+            (Side::North, Side::East) => (0, COLUMNS - 1),
+            (Side::North, _) => (0, 0),
+            (_, Side::East) => (ROWS - 1, COLUMNS - 1),
+            _ => (ROWS - 1, 0),
+        }
+    }
+
+    #[test]
+    fn opens_both_sides_of_the_diagonal() {
+        for diagonal in [
+            Diagonal::NorthEast,
+            Diagonal::NorthWest,
+            Diagonal::SouthEast,
+            Diagonal::SouthWest,
+        ]
+        .iter()
+        {
+            let grid = carve(*diagonal, 0.5);
+            let (row, column) = edges(*diagonal);
+            assert!(row_is_open(&grid, row));
+            assert!(column_is_open(&grid, column));
+
+            // Always carving one way leaves nothing to chance
+            let vertical = carve(*diagonal, 1.0);
+            assert!((0..COLUMNS).all(|column| column_is_open(&vertical, column)));
+            let horizontal = carve(*diagonal, 0.0);
+            assert!((0..ROWS).all(|row| row_is_open(&horizontal, row)));
+        }
     }
 }
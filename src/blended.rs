@@ -0,0 +1,274 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rand::seq::SliceRandom;
+
+use crate::cell::Coord;
+use crate::disjoint_set::DisjointSet;
+use crate::grid::Grid;
+use crate::mask::Mask;
+
+pub type Generator = Box<dyn Fn(&Grid)>;
+
+pub enum Region {
+    Rectangle {
+        row: usize,
+        column: usize,
+        height: usize,
+        width: usize,
+    },
+    Mask(Mask),
+    Cells(Box<dyn Fn(usize, usize) -> bool>),
+}
+
+impl Region {
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        match self {
+            Region::Rectangle {
+                row: top,
+                column: left,
+                height,
+                width,
+            } => row >= *top && row < top + height && column >= *left && column < left + width,
+            Region::Mask(mask) => mask.is_on(row, column),
+            Region::Cells(contains) => contains(row, column),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BlendError {
+    Unclaimed(Coord),
+    Disconnected { pieces: usize },
+}
+
+pub struct Blended {}
+
+impl Blended {
+    // Each active cell belongs to the first zone whose region contains it. Every connected
+    // piece of a zone is carved by that zone's generator on its own, then neighbouring pieces
+    // are stitched together with up to `passages_per_seam` passages. A stitch after the first
+    // on a seam would close a loop, so a passage inside one of the pieces on that loop is
+    // walled up to make room for it. Returns the number of stitches carved.
+    pub fn on(
+        grid: &mut Grid,
+        zones: &[(Region, Generator)],
+        passages_per_seam: usize,
+    ) -> Result<usize, BlendError> {
+        let mut zone_of = vec![vec![None; grid.columns]; grid.rows];
+        for (row, zones_row) in zone_of.iter_mut().enumerate() {
+            for (column, zone_slot) in zones_row.iter_mut().enumerate() {
+                if !grid.is_active(row, column) {
+                    continue;
+                }
+                match zones
+                    .iter()
+                    .position(|(region, _)| region.contains(row, column))
+                {
+                    Some(zone) => *zone_slot = Some(zone),
+                    None => return Err(BlendError::Unclaimed(Coord::from(row, column))),
+                }
+            }
+        }
+
+        let pieces = pieces(grid, &zone_of);
+        let mut piece_of = vec![vec![None; grid.columns]; grid.rows];
+        for (piece, cells) in pieces.iter().enumerate() {
+            for coord in cells {
+                piece_of[coord.row()][coord.column()] = Some(piece);
+            }
+        }
+
+        let original_mask = grid.mask.clone();
+        for (piece, cells) in pieces.iter().enumerate() {
+            if cells.len() < 2 {
+                continue;
+            }
+            for (row, pieces_row) in piece_of.iter().enumerate() {
+                for (column, owner) in pieces_row.iter().enumerate() {
+                    grid.mask.set(row, column, *owner == Some(piece));
+                }
+            }
+            grid.configure_cells();
+            let zone = zone_of[cells[0].row()][cells[0].column()].unwrap();
+            (zones[zone].1)(grid);
+        }
+        grid.mask = original_mask;
+        grid.configure_cells();
+
+        Blended::stitch(grid, &piece_of, pieces.len(), passages_per_seam)
+    }
+
+    fn stitch(
+        grid: &Grid,
+        piece_of: &[Vec<Option<usize>>],
+        pieces: usize,
+        passages_per_seam: usize,
+    ) -> Result<usize, BlendError> {
+        let mut seams: HashMap<(usize, usize), Vec<(Coord, Coord)>> = HashMap::new();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                let cell = grid.grid[row][column].borrow();
+                let mut neighbours = Vec::new();
+                if cell.east.is_some() {
+                    neighbours.push(Coord::from(row, column + 1));
+                }
+                if cell.south.is_some() {
+                    neighbours.push(Coord::from(row + 1, column));
+                }
+                for neighbour in neighbours {
+                    let from = piece_of[row][column].unwrap();
+                    let to = piece_of[neighbour.row()][neighbour.column()].unwrap();
+                    if from != to {
+                        seams
+                            .entry((from.min(to), from.max(to)))
+                            .or_default()
+                            .push((Coord::from(row, column), neighbour));
+                    }
+                }
+            }
+        }
+        let mut seams: Vec<_> = seams.into_iter().collect();
+        let mut rng = rand::thread_rng();
+        seams.shuffle(&mut rng);
+        let mut joined = DisjointSet::initialize(pieces);
+        let mut stitches = 0;
+        for ((from_piece, to_piece), walls) in seams.iter_mut() {
+            walls.shuffle(&mut rng);
+            for (from, to) in walls.iter().take(passages_per_seam) {
+                if joined.union(*from_piece, *to_piece) {
+                    grid.link(from, to);
+                } else {
+                    // Wall up a passage inside a piece on the loop the stitch would close
+                    let inside: Vec<_> = path_between(grid, from, to)
+                        .windows(2)
+                        .map(|pair| (pair[0].clone(), pair[1].clone()))
+                        .filter(|(a, b)| {
+                            piece_of[a.row()][a.column()] == piece_of[b.row()][b.column()]
+                        })
+                        .collect();
+                    match inside.choose(&mut rng) {
+                        Some((a, b)) => grid.unlink(a, b),
+                        None => continue,
+                    }
+                    grid.link(from, to);
+                }
+                stitches += 1;
+            }
+        }
+
+        let mut roots = HashSet::new();
+        for piece in 0..pieces {
+            roots.insert(joined.find(piece));
+        }
+        if roots.len() > 1 {
+            return Err(BlendError::Disconnected {
+                pieces: roots.len(),
+            });
+        }
+        Ok(stitches)
+    }
+}
+
+// Splits the active cells into connected pieces that each lie within one zone
+fn pieces(grid: &Grid, zone_of: &[Vec<Option<usize>>]) -> Vec<Vec<Coord>> {
+    let mut seen = vec![vec![false; grid.columns]; grid.rows];
+    let mut pieces = Vec::new();
+    for row in 0..grid.rows {
+        for column in 0..grid.columns {
+            if seen[row][column] || zone_of[row][column].is_none() {
+                continue;
+            }
+            seen[row][column] = true;
+            let mut cells = Vec::new();
+            let mut queue = VecDeque::new();
+            queue.push_back(Coord::from(row, column));
+            while let Some(coord) = queue.pop_front() {
+                for weak_ref in grid.grid[coord.row()][coord.column()].borrow().neighbours() {
+                    let neighbour = weak_ref.upgrade().unwrap();
+                    let (r, c) = (neighbour.borrow().row, neighbour.borrow().column);
+                    if !seen[r][c] && zone_of[r][c] == zone_of[row][column] {
+                        seen[r][c] = true;
+                        queue.push_back(Coord::from(r, c));
+                    }
+                }
+                cells.push(coord);
+            }
+            pieces.push(cells);
+        }
+    }
+    pieces
+}
+
+// The cells along the passages from one cell to another, both included
+fn path_between(grid: &Grid, from: &Coord, to: &Coord) -> Vec<Coord> {
+    let distances = grid.find_distances(from.clone());
+    let mut path = vec![to.clone()];
+    let mut current = to.clone();
+    while current != *from {
+        let distance = distances.get_cell_distance(&current);
+        current = grid.grid[current.row()][current.column()]
+            .borrow()
+            .links
+            .iter()
+            .find(|link| {
+                distances.has_cell(link) && distances.get_cell_distance(link) + 1 == distance
+            })
+            .unwrap()
+            .clone();
+        path.push(current.clone());
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_tree::BinaryTree;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+    use crate::topology::Topology;
+
+    #[test]
+    fn stitches_zones_into_one_perfect_maze() {
+        for passages_per_seam in 1..4 {
+            let mut grid = Grid::initialize(8, 8);
+            grid.configure_cells();
+            let zones: Vec<(Region, Generator)> = vec![
+                (
+                    Region::Rectangle {
+                        row: 0,
+                        column: 0,
+                        height: 4,
+                        width: 8,
+                    },
+                    Box::new(BinaryTree::on),
+                ),
+                (
+                    Region::Cells(Box::new(|_, column| column % 4 < 2)),
+                    Box::new(RecursiveBacktracker::on),
+                ),
+                (
+                    Region::Cells(Box::new(|_, _| true)),
+                    Box::new(BinaryTree::on),
+                ),
+            ];
+            let stitches = Blended::on(&mut grid, &zones, passages_per_seam).unwrap();
+            assert!(stitches >= 4);
+            assert_eq!(Ok(()), grid.validate());
+            assert!(grid.is_perfect());
+        }
+    }
+
+    #[test]
+    fn needs_every_cell_in_a_zone() {
+        let mut grid = Grid::initialize(4, 4);
+        grid.configure_cells();
+        let zones: Vec<(Region, Generator)> = vec![(
+            Region::Cells(Box::new(|row, _| row < 3)),
+            Box::new(BinaryTree::on),
+        )];
+        assert_eq!(
+            Err(BlendError::Unclaimed(Coord::from(3, 0))),
+            Blended::on(&mut grid, &zones, 1)
+        );
+    }
+}
@@ -23,6 +23,14 @@ impl Coord {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    North,
+    South,
+    East,
+    West,
+}
+
 #[derive(Debug)]
 pub struct GridCell {
     pub row: usize,
@@ -75,6 +83,15 @@ impl GridCell {
         self.links.contains(coord)
     }
 
+    pub fn neighbour(&self, side: Side) -> Option<GridCellRefWeak> {
+        match side {
+            Side::North => self.north.clone(),
+            Side::South => self.south.clone(),
+            Side::East => self.east.clone(),
+            Side::West => self.west.clone(),
+        }
+    }
+
     pub fn neighbours(&self) -> Vec<GridCellRefWeak> {
         let mut neighbours = Vec::new();
         if let Some(cell) = &self.north {
@@ -167,6 +184,21 @@ mod tests {
         assert_eq!(1, cell_a.borrow().neighbours().len());
     }
 
+    #[test]
+    fn returns_neighbour_on_side() {
+        let cell_a = GridCell::init(1, 1);
+        let cell_b = GridCell::init(1, 2);
+        cell_a.borrow_mut().east = Some(Rc::downgrade(&cell_b));
+        let eastern = cell_a
+            .borrow()
+            .neighbour(Side::East)
+            .unwrap()
+            .upgrade()
+            .unwrap();
+        assert_eq!((1, 2), (eastern.borrow().row, eastern.borrow().column));
+        assert!(cell_a.borrow().neighbour(Side::West).is_none());
+    }
+
     #[test]
     fn reset_removes_links() {
         let cell_a = GridCell::init(0, 0);
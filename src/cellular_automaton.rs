@@ -0,0 +1,152 @@
+use rand::Rng;
+
+use crate::cell::Coord;
+use crate::grid::Grid;
+
+pub const MAZE: &str = "B3/S12345";
+pub const MAZECTRIC: &str = "B3/S1234";
+
+const INITIAL_DENSITY: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl Rule {
+    pub fn parse(rule: &str) -> Option<Rule> {
+        let mut birth = [false; 9];
+        let mut survival = [false; 9];
+        for part in rule.split('/') {
+            let mut chars = part.chars();
+            let counts = match chars.next() {
+                Some('B') | Some('b') => &mut birth,
+                Some('S') | Some('s') => &mut survival,
+                _ => return None,
+            };
+            for digit in chars {
+                counts[digit.to_digit(9)? as usize] = true;
+            }
+        }
+        Some(Rule { birth, survival })
+    }
+
+    fn next_state(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survival[neighbours]
+        } else {
+            self.birth[neighbours]
+        }
+    }
+}
+
+pub struct CellularAutomaton {}
+
+impl CellularAutomaton {
+    pub fn on(grid: &Grid, rule: &Rule, iterations: usize) {
+        CellularAutomaton::on_with_rng(grid, rule, iterations, &mut rand::thread_rng());
+    }
+
+    pub fn on_with_rng<R: Rng>(grid: &Grid, rule: &Rule, iterations: usize, rng: &mut R) {
+        let height = 2 * grid.rows - 1;
+        let width = 2 * grid.columns - 1;
+        let mut walls: Vec<Vec<bool>> = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| rng.gen::<f64>() < INITIAL_DENSITY)
+                    .collect()
+            })
+            .collect();
+        for _ in 0..iterations {
+            walls = (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| rule.next_state(walls[y][x], live_neighbours(&walls, y, x)))
+                        .collect()
+                })
+                .collect();
+        }
+
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                if !grid.is_active(row, column) {
+                    continue;
+                }
+                if grid.is_active(row, column + 1) && !walls[2 * row][2 * column + 1] {
+                    grid.link(&Coord::from(row, column), &Coord::from(row, column + 1));
+                }
+                if grid.is_active(row + 1, column) && !walls[2 * row + 1][2 * column] {
+                    grid.link(&Coord::from(row, column), &Coord::from(row + 1, column));
+                }
+            }
+        }
+        grid.join_regions(rng);
+    }
+}
+
+fn live_neighbours(walls: &[Vec<bool>], y: usize, x: usize) -> usize {
+    let rows = &walls[y.saturating_sub(1)..(y + 2).min(walls.len())];
+    let own = if walls[y][x] { 1 } else { 0 };
+    rows.iter()
+        .map(|row| {
+            row[x.saturating_sub(1)..(x + 2).min(row.len())]
+                .iter()
+                .filter(|alive| **alive)
+                .count()
+        })
+        .sum::<usize>()
+        - own
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn parses_birth_and_survival_counts() {
+        let rule = Rule::parse(MAZE).unwrap();
+        assert_eq!(vec![3], counts(&rule.birth));
+        assert_eq!(vec![1, 2, 3, 4, 5], counts(&rule.survival));
+        assert_eq!(Some(rule), Rule::parse("s12345/b3"));
+        assert_eq!(Rule::parse("B3/S"), Rule::parse("B3"));
+        assert!(Rule::parse("B/S").is_some());
+    }
+
+    #[test]
+    fn knows_the_maze_rules() {
+        let mazectric = Rule::parse(MAZECTRIC).unwrap();
+        assert_eq!(vec![3], counts(&mazectric.birth));
+        assert_eq!(vec![1, 2, 3, 4], counts(&mazectric.survival));
+        assert_ne!(Rule::parse(MAZE), Some(mazectric));
+    }
+
+    #[test]
+    fn reaches_every_cell() {
+        for (seed, rule) in [MAZE, MAZECTRIC].iter().enumerate() {
+            let mut grid = Grid::initialize(15, 20);
+            grid.configure_cells();
+            let rule = Rule::parse(rule).unwrap();
+            CellularAutomaton::on_with_rng(
+                &grid,
+                &rule,
+                10,
+                &mut StdRng::seed_from_u64(seed as u64),
+            );
+            assert_eq!(Ok(()), grid.validate());
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        for rule in ["", "B3/S1x", "B39/S1", "3/S12", "B3//S1", "C3/S1"].iter() {
+            assert_eq!(None, Rule::parse(rule), "{}", rule);
+        }
+    }
+
+    fn counts(states: &[bool; 9]) -> Vec<usize> {
+        (0..9).filter(|count| states[*count]).collect()
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::cell::Coord;
+use crate::grid::Grid;
+
+pub type Constraint = Box<dyn Fn(&Metrics) -> bool>;
+
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    pub size: usize,
+    pub deadends: usize,
+    pub longest_path: usize,
+    pub solution_length: Option<usize>,
+}
+
+impl Metrics {
+    pub fn measure(grid: &Grid, start: &Coord, goal: &Coord) -> Metrics {
+        let first = grid
+            .mask
+            .first_location()
+            .unwrap_or_else(|| Coord::from(0, 0));
+        let (farthest, _) = grid.find_distances(first).max();
+        let (_, longest_path) = grid.find_distances(farthest).max();
+        let solution_length = grid.find_distances(start.clone()).cells.get(goal).cloned();
+        Metrics {
+            size: grid.size(),
+            deadends: grid.deadends(),
+            longest_path,
+            solution_length,
+        }
+    }
+
+    pub fn deadend_ratio(&self) -> f64 {
+        self.deadends as f64 / self.size as f64
+    }
+}
+
+pub struct Constraints {
+    pub start: Coord,
+    pub goal: Coord,
+    pub checks: Vec<Constraint>,
+    pub seeds: usize,
+    pub mutations_per_seed: usize,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub attempts: usize,
+    pub mutations: usize,
+    pub seed: u64,
+    pub satisfied: usize,
+    pub metrics: Metrics,
+}
+
+pub struct Constrained {}
+
+impl Constrained {
+    pub fn on<F: Fn(&Grid, &mut StdRng)>(
+        grid: &mut Grid,
+        generator: F,
+        constraints: &Constraints,
+    ) -> Result<Report, Report> {
+        Constrained::on_with_rng(grid, generator, constraints, &mut rand::thread_rng())
+    }
+
+    // Leaves the grid holding the first maze that meets every constraint, or the best one found
+    // once the budget of seeds and mutations is spent.
+    pub fn on_with_rng<F: Fn(&Grid, &mut StdRng), R: Rng>(
+        grid: &mut Grid,
+        generator: F,
+        constraints: &Constraints,
+        rng: &mut R,
+    ) -> Result<Report, Report> {
+        // With no seeds to try, the report describes the empty grid left behind
+        if constraints.seeds == 0 {
+            grid.reset();
+            let (satisfied, metrics) = Constrained::evaluate(grid, constraints);
+            return Err(Report {
+                attempts: 0,
+                mutations: 0,
+                seed: 0,
+                satisfied,
+                metrics,
+            });
+        }
+        let mut best: Option<(Report, Vec<Vec<Coord>>)> = None;
+        let mut mutations = 0;
+        for attempt in 1..=constraints.seeds {
+            let seed = rng.gen::<u64>();
+            let mut seeded_rng = StdRng::seed_from_u64(seed);
+            grid.reset();
+            generator(grid, &mut seeded_rng);
+            let mut current = 0;
+            for mutation in 0..=constraints.mutations_per_seed {
+                let swap = if mutation == 0 {
+                    None
+                } else {
+                    match mutate(grid, &mut seeded_rng) {
+                        Some(swap) => Some(swap),
+                        None => break,
+                    }
+                };
+                let (satisfied, metrics) = Constrained::evaluate(grid, constraints);
+                if let Some((removed, added)) = swap {
+                    mutations += 1;
+                    if satisfied < current {
+                        grid.unlink(&added.0, &added.1);
+                        grid.link(&removed.0, &removed.1);
+                        continue;
+                    }
+                }
+                current = satisfied;
+                let improved = match &best {
+                    Some((report, _)) => satisfied > report.satisfied,
+                    None => true,
+                };
+                if improved {
+                    let report = Report {
+                        attempts: attempt,
+                        mutations,
+                        seed,
+                        satisfied,
+                        metrics,
+                    };
+                    best = Some((report, snapshot(grid)));
+                }
+                if satisfied == constraints.checks.len() {
+                    return Ok(best.unwrap().0);
+                }
+            }
+        }
+
+        let (mut report, links) = best.expect("At least one seed must be tried");
+        report.attempts = constraints.seeds;
+        report.mutations = mutations;
+        restore(grid, &links);
+        Err(report)
+    }
+
+    fn evaluate(grid: &Grid, constraints: &Constraints) -> (usize, Metrics) {
+        let metrics = Metrics::measure(grid, &constraints.start, &constraints.goal);
+        let satisfied = constraints
+            .checks
+            .iter()
+            .filter(|check| check(&metrics))
+            .count();
+        (satisfied, metrics)
+    }
+}
+
+fn snapshot(grid: &Grid) -> Vec<Vec<Coord>> {
+    grid.grid
+        .iter()
+        .flatten()
+        .map(|cell| cell.borrow().get_links())
+        .collect()
+}
+
+fn restore(grid: &mut Grid, links: &[Vec<Coord>]) {
+    grid.reset();
+    for (cell, cell_links) in grid.grid.iter().flatten().zip(links) {
+        cell.borrow_mut().links = cell_links.iter().cloned().collect();
+    }
+}
+
+type Passage = (Coord, Coord);
+
+// Swaps one passage for another wall across the cut it leaves, so a perfect maze stays perfect
+fn mutate<R: Rng>(grid: &Grid, rng: &mut R) -> Option<(Passage, Passage)> {
+    let mut passages = Vec::new();
+    for cell in grid.grid.iter().flatten() {
+        let cell = cell.borrow();
+        for link in &cell.links {
+            if (cell.row, cell.column) < (link.row(), link.column()) {
+                passages.push((Coord::from(cell.row, cell.column), link.clone()));
+            }
+        }
+    }
+    if passages.is_empty() {
+        return None;
+    }
+    passages.sort_by_key(sort_key);
+    let removed = passages.swap_remove(rng.gen_range(0, passages.len()));
+    grid.unlink(&removed.0, &removed.1);
+
+    let mut component = HashSet::new();
+    let mut frontier = vec![removed.0.clone()];
+    component.insert(removed.0.clone());
+    while let Some(coord) = frontier.pop() {
+        for link in grid.grid[coord.row()][coord.column()].borrow().get_links() {
+            if component.insert(link.clone()) {
+                frontier.push(link);
+            }
+        }
+    }
+    let mut walls = Vec::new();
+    for coord in &component {
+        for neighbour in grid.grid[coord.row()][coord.column()].borrow().neighbours() {
+            let neighbour = neighbour.upgrade().unwrap();
+            let neighbour = Coord::from(neighbour.borrow().row, neighbour.borrow().column);
+            if !component.contains(&neighbour) && (coord, &neighbour) != (&removed.0, &removed.1) {
+                walls.push((coord.clone(), neighbour));
+            }
+        }
+    }
+    if walls.is_empty() {
+        grid.link(&removed.0, &removed.1);
+        return None;
+    }
+    walls.sort_by_key(sort_key);
+    let added = walls.swap_remove(rng.gen_range(0, walls.len()));
+    grid.link(&added.0, &added.1);
+    Some((removed, added))
+}
+
+fn sort_key((from, to): &Passage) -> (usize, usize, usize, usize) {
+    (from.row(), from.column(), to.row(), to.column())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+
+    fn constraints(seeds: usize) -> Constraints {
+        Constraints {
+            start: Coord::from(0, 0),
+            goal: Coord::from(4, 4),
+            checks: vec![Box::new(|metrics| metrics.solution_length.is_some())],
+            seeds,
+            mutations_per_seed: 5,
+        }
+    }
+
+    #[test]
+    fn fails_cleanly_without_seeds() {
+        let mut grid = Grid::initialize(5, 5);
+        grid.configure_cells();
+        let outcome = Constrained::on(
+            &mut grid,
+            RecursiveBacktracker::on_with_rng,
+            &constraints(0),
+        );
+        let report = outcome.unwrap_err();
+        assert_eq!(
+            (0, 0, 0),
+            (report.attempts, report.mutations, report.satisfied)
+        );
+        assert_eq!(None, report.metrics.solution_length);
+    }
+
+    #[test]
+    fn keeps_the_first_maze_that_fits() {
+        let mut grid = Grid::initialize(5, 5);
+        grid.configure_cells();
+        let outcome = Constrained::on(
+            &mut grid,
+            RecursiveBacktracker::on_with_rng,
+            &constraints(3),
+        );
+        let report = outcome.unwrap();
+        assert_eq!(1, report.attempts);
+        assert!(grid.validate().is_ok());
+    }
+}
@@ -0,0 +1,124 @@
+use std::fs::File;
+
+use cairo::{ImageSurface, IoError};
+use rand::Rng;
+
+use crate::cell::Coord;
+
+// Weighted generators add up to this much random cost, so one field still gives many mazes
+pub const JITTER: f64 = 0.1;
+
+// A cost between 0.0 and 1.0 for every cell. Cheap cells are carved first by the weighted
+// generators, so passages gather along the cheap (dark) features of the field.
+#[derive(Debug, Clone)]
+pub struct CostField {
+    costs: Vec<Vec<f64>>,
+}
+
+impl CostField {
+    pub fn uniform(rows: usize, columns: usize) -> CostField {
+        CostField {
+            costs: vec![vec![0.5; columns]; rows],
+        }
+    }
+
+    pub fn from_fn<F: Fn(usize, usize) -> f64>(rows: usize, columns: usize, cost: F) -> CostField {
+        CostField {
+            costs: (0..rows)
+                .map(|row| {
+                    (0..columns)
+                        .map(|column| cost(row, column).clamp(0.0, 1.0))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    // Samples the brightness of the nearest pixel to each cell's centre, so the image can be
+    // any size. Black pixels cost nothing and white ones cost the most.
+    pub fn from_png(filename: &str, rows: usize, columns: usize) -> Result<CostField, IoError> {
+        let mut file = File::open(filename).map_err(IoError::Io)?;
+        let mut surface = ImageSurface::create_from_png(&mut file)?;
+        let height = surface.get_height() as usize;
+        let width = surface.get_width() as usize;
+        let stride = surface.get_stride() as usize;
+        let data = surface
+            .get_data()
+            .expect("Couldn't read the pixels of the cost image");
+        let mut field = CostField::uniform(rows, columns);
+        if width == 0 || height == 0 {
+            return Ok(field);
+        }
+        for (row, costs) in field.costs.iter_mut().enumerate() {
+            for (column, cost) in costs.iter_mut().enumerate() {
+                let y = ((row as f64 + 0.5) * height as f64 / rows as f64) as usize;
+                let x = ((column as f64 + 0.5) * width as f64 / columns as f64) as usize;
+                let offset = y.min(height - 1) * stride + x.min(width - 1) * 4;
+                let pixel = &data[offset..offset + 3];
+                *cost = pixel.iter().map(|channel| *channel as f64).sum::<f64>() / (3.0 * 255.0);
+            }
+        }
+        Ok(field)
+    }
+
+    // Value noise: random costs on a lattice `scale` cells apart, smoothly blended in between
+    pub fn value_noise<R: Rng>(rows: usize, columns: usize, scale: f64, rng: &mut R) -> CostField {
+        let scale = scale.max(1.0);
+        let lattice_rows = (rows as f64 / scale).ceil() as usize + 2;
+        let lattice_columns = (columns as f64 / scale).ceil() as usize + 2;
+        let lattice: Vec<Vec<f64>> = (0..lattice_rows)
+            .map(|_| (0..lattice_columns).map(|_| rng.gen::<f64>()).collect())
+            .collect();
+        let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+
+        let mut field = CostField::uniform(rows, columns);
+        for (row, costs) in field.costs.iter_mut().enumerate() {
+            for (column, cost) in costs.iter_mut().enumerate() {
+                let y = row as f64 / scale;
+                let x = column as f64 / scale;
+                let (top, left) = (y.floor() as usize, x.floor() as usize);
+                let (dy, dx) = (smooth(y.fract()), smooth(x.fract()));
+                let upper = lattice[top][left] * (1.0 - dx) + lattice[top][left + 1] * dx;
+                let lower = lattice[top + 1][left] * (1.0 - dx) + lattice[top + 1][left + 1] * dx;
+                *cost = upper * (1.0 - dy) + lower * dy;
+            }
+        }
+        field
+    }
+
+    pub fn cost(&self, row: usize, column: usize) -> f64 {
+        self.costs
+            .get(row)
+            .and_then(|costs| costs.get(column))
+            .cloned()
+            .unwrap_or(1.0)
+    }
+
+    // The cost of the passage between two neighbouring cells
+    pub fn edge_cost(&self, from: &Coord, to: &Coord) -> f64 {
+        (self.cost(from.row(), from.column()) + self.cost(to.row(), to.column())) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn value_noise_stays_in_range_and_varies_smoothly() {
+        let field = CostField::value_noise(12, 16, 4.0, &mut StdRng::seed_from_u64(3));
+        for row in 0..12 {
+            for column in 0..16 {
+                let cost = field.cost(row, column);
+                assert!((0.0..=1.0).contains(&cost));
+                if column > 0 {
+                    assert!((cost - field.cost(row, column - 1)).abs() < 0.5);
+                }
+            }
+        }
+        let edge = field.edge_cost(&Coord::from(0, 0), &Coord::from(0, 1));
+        assert!((edge - (field.cost(0, 0) + field.cost(0, 1)) / 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,37 @@
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    pub fn initialize(size: usize) -> DisjointSet {
+        DisjointSet {
+            parents: (0..size).collect(),
+        }
+    }
+
+    pub fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = index;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    // Returns false when both were already in the same set
+    pub fn union(&mut self, left: usize, right: usize) -> bool {
+        let left = self.find(left);
+        let right = self.find(right);
+        if left == right {
+            return false;
+        }
+        self.parents[left] = right;
+        true
+    }
+}
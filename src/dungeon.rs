@@ -0,0 +1,205 @@
+use crate::cell::Coord;
+use crate::grid::Grid;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tag {
+    Rock,
+    Room(usize),
+    Corridor,
+    Door,
+}
+
+#[derive(Debug, Clone)]
+pub struct Room {
+    pub row: usize,
+    pub column: usize,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Room {
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        row >= self.row
+            && row < self.row + self.height
+            && column >= self.column
+            && column < self.column + self.width
+    }
+
+    // Rooms keep at least one corridor cell between them so the maze can run around every room
+    fn is_clear_of(&self, other: &Room) -> bool {
+        self.row > other.row + other.height
+            || other.row > self.row + self.height
+            || self.column > other.column + other.width
+            || other.column > self.column + self.width
+    }
+}
+
+pub struct DungeonOptions {
+    pub room_attempts: usize,
+    pub min_room_size: usize,
+    pub max_room_size: usize,
+    pub doors_per_room: usize,
+    pub cull: Option<f64>,
+}
+
+impl Default for DungeonOptions {
+    fn default() -> Self {
+        DungeonOptions {
+            room_attempts: 30,
+            min_room_size: 2,
+            max_room_size: 5,
+            doors_per_room: 1,
+            cull: None,
+        }
+    }
+}
+
+pub struct Dungeon {
+    pub rooms: Vec<Room>,
+    pub tags: Vec<Vec<Tag>>,
+}
+
+impl Dungeon {
+    pub fn on<F: Fn(&Grid)>(grid: &mut Grid, options: &DungeonOptions, generator: F) -> Dungeon {
+        let rooms = Dungeon::place_rooms(grid, options);
+
+        for room in &rooms {
+            for_each_cell(room, |row, column| grid.mask.set(row, column, false));
+        }
+        grid.configure_cells();
+        generator(grid);
+        for room in &rooms {
+            for_each_cell(room, |row, column| grid.mask.set(row, column, true));
+        }
+        grid.configure_cells();
+
+        let mut tags = (0..grid.rows)
+            .map(|row| {
+                (0..grid.columns)
+                    .map(|column| {
+                        if grid.is_active(row, column) {
+                            Tag::Corridor
+                        } else {
+                            Tag::Rock
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for (index, room) in rooms.iter().enumerate() {
+            for_each_cell(room, |row, column| {
+                tags[row][column] = Tag::Room(index);
+                if column + 1 < room.column + room.width {
+                    grid.link(&Coord::from(row, column), &Coord::from(row, column + 1));
+                }
+                if row + 1 < room.row + room.height {
+                    grid.link(&Coord::from(row, column), &Coord::from(row + 1, column));
+                }
+            });
+            for (inside, outside) in Dungeon::choose_doors(grid, room, options.doors_per_room) {
+                grid.link(&inside, &outside);
+                tags[outside.row()][outside.column()] = Tag::Door;
+            }
+        }
+
+        if let Some(solid_fraction) = options.cull {
+            grid.cull_deadends(solid_fraction);
+            for (row, row_tags) in tags.iter_mut().enumerate() {
+                for (column, tag) in row_tags.iter_mut().enumerate() {
+                    if !grid.is_active(row, column) {
+                        *tag = Tag::Rock;
+                    }
+                }
+            }
+        }
+        Dungeon { rooms, tags }
+    }
+
+    fn place_rooms(grid: &Grid, options: &DungeonOptions) -> Vec<Room> {
+        let mut rooms: Vec<Room> = Vec::new();
+        let size_range =
+            options.max_room_size.max(options.min_room_size) - options.min_room_size + 1;
+        for _ in 0..options.room_attempts {
+            let height = options.min_room_size + rand::random::<usize>() % size_range;
+            let width = options.min_room_size + rand::random::<usize>() % size_range;
+            if height + 2 > grid.rows || width + 2 > grid.columns {
+                continue;
+            }
+            let room = Room {
+                row: 1 + rand::random::<usize>() % (grid.rows - height - 1),
+                column: 1 + rand::random::<usize>() % (grid.columns - width - 1),
+                height,
+                width,
+            };
+            let mut inside_mask = true;
+            for_each_cell(&room, |row, column| {
+                inside_mask &= grid.is_active(row, column);
+            });
+            if inside_mask && rooms.iter().all(|other| room.is_clear_of(other)) {
+                rooms.push(room);
+            }
+        }
+        rooms
+    }
+
+    fn choose_doors(grid: &Grid, room: &Room, doors: usize) -> Vec<(Coord, Coord)> {
+        let mut walls = Vec::new();
+        for_each_cell(room, |row, column| {
+            let outside = [
+                Coord::from(row - 1, column),
+                Coord::from(row + 1, column),
+                Coord::from(row, column - 1),
+                Coord::from(row, column + 1),
+            ];
+            for coord in outside.iter() {
+                if !room.contains(coord.row(), coord.column())
+                    && grid.is_active(coord.row(), coord.column())
+                {
+                    walls.push((Coord::from(row, column), coord.clone()));
+                }
+            }
+        });
+        let mut chosen = Vec::new();
+        while chosen.len() < doors && !walls.is_empty() {
+            chosen.push(walls.swap_remove(rand::random::<usize>() % walls.len()));
+        }
+        chosen
+    }
+}
+
+fn for_each_cell<F: FnMut(usize, usize)>(room: &Room, mut f: F) {
+    for row in room.row..room.row + room.height {
+        for column in room.column..room.column + room.width {
+            f(row, column);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+
+    #[test]
+    fn joins_rooms_and_corridors_into_one_region() {
+        for cull in [None, Some(0.6)].iter() {
+            for _ in 0..20 {
+                let mut grid = Grid::initialize(20, 30);
+                grid.configure_cells();
+                let options = DungeonOptions {
+                    cull: *cull,
+                    ..DungeonOptions::default()
+                };
+                let dungeon = Dungeon::on(&mut grid, &options, RecursiveBacktracker::on);
+                assert!(!dungeon.rooms.is_empty());
+                assert_eq!(Ok(()), grid.validate());
+                for (index, room) in dungeon.rooms.iter().enumerate() {
+                    assert_eq!(Tag::Room(index), dungeon.tags[room.row][room.column]);
+                    assert!(grid.is_active(room.row, room.column));
+                }
+                // Rooms one cell apart can share a door
+                assert!(dungeon.tags.iter().flatten().any(|tag| *tag == Tag::Door));
+            }
+        }
+    }
+}
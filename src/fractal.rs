@@ -0,0 +1,126 @@
+use rand::Rng;
+
+use crate::cell::Coord;
+use crate::cost_field::CostField;
+use crate::grid::Grid;
+use crate::render::Renderable;
+
+pub struct Fractal {
+    pub grid: Grid,
+    pub factor: usize,
+    pub depth: usize,
+}
+
+impl Fractal {
+    // Carves a rows x columns maze, then `depth` times replaces every cell with a
+    // factor x factor maze of its own. Neighbouring blocks are joined by a single passage
+    // wherever the coarser cells were, so each level is still a perfect maze.
+    pub fn on<F: Fn(&Grid)>(
+        rows: usize,
+        columns: usize,
+        factor: usize,
+        depth: usize,
+        generator: F,
+    ) -> Fractal {
+        let mut grid = Grid::initialize(rows, columns);
+        grid.configure_cells();
+        generator(&grid);
+        for _ in 0..depth {
+            grid = Fractal::refine(&grid, factor, &generator);
+        }
+        Fractal {
+            grid,
+            factor,
+            depth,
+        }
+    }
+
+    // The cell that contains `coord` at `level`, where level 0 is the coarsest maze and
+    // level `depth` is the finished one
+    pub fn ancestor(&self, coord: &Coord, level: usize) -> Coord {
+        let scale = self.factor.pow((self.depth - level.min(self.depth)) as u32);
+        Coord::from(coord.row() / scale, coord.column() / scale)
+    }
+
+    fn refine<F: Fn(&Grid)>(coarse: &Grid, factor: usize, generator: &F) -> Grid {
+        let mut fine = Grid::initialize(coarse.rows * factor, coarse.columns * factor);
+        fine.configure_cells();
+        let mut rng = rand::thread_rng();
+        for row in 0..coarse.rows {
+            for column in 0..coarse.columns {
+                let mut block = Grid::initialize(factor, factor);
+                block.configure_cells();
+                generator(&block);
+                let (top, left) = (row * factor, column * factor);
+                for cell in block.grid.iter().flatten() {
+                    let cell = cell.borrow();
+                    let from = Coord::from(top + cell.row, left + cell.column);
+                    for link in &cell.links {
+                        fine.link(&from, &Coord::from(top + link.row(), left + link.column()));
+                    }
+                }
+
+                // One passage through the shared edge for every coarse passage east or south
+                let links = coarse.grid[row][column].borrow().get_links();
+                let offset = rng.gen_range(0, factor);
+                if links.contains(&Coord::from(row, column + 1)) {
+                    let from = Coord::from(top + offset, left + factor - 1);
+                    fine.link(&from, &Coord::from(top + offset, left + factor));
+                }
+                let offset = rng.gen_range(0, factor);
+                if links.contains(&Coord::from(row + 1, column)) {
+                    let from = Coord::from(top + factor - 1, left + offset);
+                    fine.link(&from, &Coord::from(top + factor, left + offset));
+                }
+            }
+        }
+        fine
+    }
+
+    // Tints every cell by the parity of its ancestors, strongest for the coarsest level
+    fn shading(&self) -> CostField {
+        CostField::from_fn(self.grid.rows, self.grid.columns, |row, column| {
+            let coord = Coord::from(row, column);
+            let mut shade = 0.5;
+            let mut weight = 0.25;
+            for level in 0..self.depth {
+                let ancestor = self.ancestor(&coord, level);
+                if (ancestor.row() + ancestor.column()).is_multiple_of(2) {
+                    shade += weight;
+                } else {
+                    shade -= weight;
+                }
+                weight /= 2.0;
+            }
+            shade
+        })
+    }
+}
+
+impl Renderable for Fractal {
+    fn to_png(&self, filename: &str) {
+        let mut grid = self.grid.clone();
+        grid.background = Some(self.shading());
+        grid.to_png(filename);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+    use crate::topology::Topology;
+
+    #[test]
+    fn refines_into_a_perfect_maze() {
+        let fractal = Fractal::on(3, 2, 3, 2, RecursiveBacktracker::on);
+        assert_eq!((27, 18), (fractal.grid.rows, fractal.grid.columns));
+        assert_eq!(Ok(()), fractal.grid.validate());
+        assert!(fractal.grid.is_perfect());
+
+        let coord = Coord::from(26, 10);
+        assert_eq!(Coord::from(2, 1), fractal.ancestor(&coord, 0));
+        assert_eq!(Coord::from(8, 3), fractal.ancestor(&coord, 1));
+        assert_eq!(coord, fractal.ancestor(&coord, 2));
+    }
+}
@@ -1,18 +1,33 @@
 use std::rc::Rc;
 
 use rand;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
+use crate::cost_field::CostField;
+use crate::disjoint_set::DisjointSet;
 use crate::distances::Distances;
+use crate::mask::{Mask, MaskError};
+use crate::topology::Topology;
 use crate::{cell, cell::Coord};
 
 type GridCells = Vec<Vec<cell::GridCellRef>>;
 
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    OneWayLink(Coord, Coord),
+    NotNeighbours(Coord, Coord),
+    Disconnected { reached: usize, size: usize },
+}
+
 #[derive(Debug, Clone)]
 pub struct Grid {
     pub rows: usize,
     pub columns: usize,
     pub grid: GridCells,
     pub distances: Distances,
+    pub mask: Mask,
+    pub background: Option<CostField>,
 }
 
 impl Grid {
@@ -22,9 +37,21 @@ impl Grid {
             columns: columns,
             grid: Grid::prepare_grid(rows, columns),
             distances: Distances::initialize(Coord::from(0, 0)),
+            mask: Mask::initialize(rows, columns),
+            background: None,
         }
     }
 
+    // A grid in the shape of the mask. Generators only ever reach the cells that are on,
+    // so a mask whose on cells don't form one connected region is rejected.
+    pub fn from_mask(mask: Mask) -> Result<Grid, MaskError> {
+        mask.validate()?;
+        let mut grid = Grid::initialize(mask.rows, mask.columns);
+        grid.mask = mask;
+        grid.configure_cells();
+        Ok(grid)
+    }
+
     pub fn prepare_grid(rows: usize, columns: usize) -> GridCells {
         (0..rows)
             .map(|r| {
@@ -39,22 +66,29 @@ impl Grid {
         for row in 0..self.rows {
             for column in 0..self.columns {
                 let mut cell = self.grid[row][column].borrow_mut();
-                let north = if row > 0 {
+                if !self.is_active(row, column) {
+                    cell.north = None;
+                    cell.east = None;
+                    cell.west = None;
+                    cell.south = None;
+                    continue;
+                }
+                let north = if row > 0 && self.is_active(row - 1, column) {
                     self.get_cell_ref(row - 1, column)
                 } else {
                     None
                 };
-                let east = if column + 1 < self.columns {
+                let east = if self.is_active(row, column + 1) {
                     self.get_cell_ref(row, column + 1)
                 } else {
                     None
                 };
-                let west = if column > 0 {
+                let west = if column > 0 && self.is_active(row, column - 1) {
                     self.get_cell_ref(row, column - 1)
                 } else {
                     None
                 };
-                let south = if row + 1 < self.rows {
+                let south = if self.is_active(row + 1, column) {
                     self.get_cell_ref(row + 1, column)
                 } else {
                     None
@@ -67,6 +101,33 @@ impl Grid {
         }
     }
 
+    pub fn is_active(&self, row: usize, column: usize) -> bool {
+        self.mask.is_on(row, column)
+    }
+
+    // Turns a cell into solid rock: its passages are closed and no neighbour can reach it
+    pub fn deactivate(&mut self, coord: &Coord) {
+        let mut cell = self.grid[coord.row()][coord.column()].borrow_mut();
+        for link in cell.get_links() {
+            let linked = &self.grid[link.row()][link.column()];
+            linked.borrow_mut().links.remove(coord);
+        }
+        cell.reset();
+        if let Some(north) = cell.north.take() {
+            north.upgrade().unwrap().borrow_mut().south = None;
+        }
+        if let Some(east) = cell.east.take() {
+            east.upgrade().unwrap().borrow_mut().west = None;
+        }
+        if let Some(west) = cell.west.take() {
+            west.upgrade().unwrap().borrow_mut().east = None;
+        }
+        if let Some(south) = cell.south.take() {
+            south.upgrade().unwrap().borrow_mut().north = None;
+        }
+        self.mask.set(coord.row(), coord.column(), false);
+    }
+
     pub fn get_cell_ref(&self, row: usize, column: usize) -> Option<cell::GridCellRefWeak> {
         if let Some(row) = self.grid.get(row) {
             if let Some(cell) = row.get(column) {
@@ -79,6 +140,18 @@ impl Grid {
         }
     }
 
+    pub fn link(&self, left: &Coord, right: &Coord) {
+        let left_cell = &self.grid[left.row()][left.column()];
+        let right_cell = &self.grid[right.row()][right.column()];
+        cell::GridCell::link(&mut left_cell.borrow_mut(), &mut right_cell.borrow_mut());
+    }
+
+    pub fn unlink(&self, left: &Coord, right: &Coord) {
+        let left_cell = &self.grid[left.row()][left.column()];
+        let right_cell = &self.grid[right.row()][right.column()];
+        cell::GridCell::unlink(&mut left_cell.borrow_mut(), &mut right_cell.borrow_mut());
+    }
+
     pub fn reset(&mut self) {
         for row in 0..self.rows {
             for col in 0..self.columns {
@@ -89,14 +162,29 @@ impl Grid {
         self.distances = Distances::initialize(Coord::from(0, 0));
     }
 
+    // Walls only stand next to active cells, so solid regions are left blank
+    pub fn has_wall(&self, from: &Coord, to: &Coord) -> bool {
+        let from_active = self.is_active(from.row(), from.column());
+        let to_active = self.is_active(to.row(), to.column());
+        if from_active && to_active {
+            !self.grid[from.row()][from.column()].borrow().is_linked(to)
+        } else {
+            from_active || to_active
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn get_random_cell(&self) -> cell::GridCellRefWeak {
-        let rand_row = rand::random::<usize>() % self.rows;
-        let rand_col = rand::random::<usize>() % self.columns;
-        self.get_cell_ref(rand_row, rand_col).unwrap()
+        self.get_random_cell_with(&mut rand::thread_rng())
+    }
+
+    pub fn get_random_cell_with<R: Rng>(&self, rng: &mut R) -> cell::GridCellRefWeak {
+        let location = self.random_cell(rng);
+        Rc::downgrade(&self.grid[location.row()][location.column()])
     }
 
     pub fn size(&self) -> usize {
-        self.rows * self.columns
+        self.mask.count()
     }
 
     pub fn find_distances(&self, start: Coord) -> Distances {
@@ -160,58 +248,345 @@ impl Grid {
     }
 
     pub fn longest_path(&mut self) -> Distances {
-        self.distances = self.find_distances(Coord::from(0, 0));
+        let start = self
+            .mask
+            .first_location()
+            .unwrap_or_else(|| Coord::from(0, 0));
+        self.distances = self.find_distances(start);
         let (new_start, _) = self.distances.max();
         self.distances = self.find_distances(new_start);
         let (goal, _) = self.distances.max();
         self.path_to(goal)
     }
 
+    pub fn validate(&self) -> Result<(), GridError> {
+        for row in &self.grid {
+            for cell in row {
+                let cell = cell.borrow();
+                let coord = Coord::from(cell.row, cell.column);
+                let neighbours = cell
+                    .neighbours()
+                    .into_iter()
+                    .map(|weak_ref| {
+                        let neighbour = weak_ref.upgrade().unwrap();
+                        let neighbour = neighbour.borrow();
+                        Coord::from(neighbour.row, neighbour.column)
+                    })
+                    .collect::<Vec<_>>();
+                for link in &cell.links {
+                    if !neighbours.contains(link) {
+                        return Err(GridError::NotNeighbours(coord, link.clone()));
+                    }
+                    if !self.grid[link.row()][link.column()]
+                        .borrow()
+                        .is_linked(&coord)
+                    {
+                        return Err(GridError::OneWayLink(coord, link.clone()));
+                    }
+                }
+            }
+        }
+        if let Some(start) = self.mask.first_location() {
+            let reached = self.find_distances(start).cells.len();
+            if reached != self.size() {
+                return Err(GridError::Disconnected {
+                    reached,
+                    size: self.size(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Knocks down random walls between separate regions until every active cell is reachable,
+    // as far as the mask and any detached neighbours allow. Returns the number of passages carved.
+    pub fn join_regions<R: Rng>(&self, rng: &mut R) -> usize {
+        let index = |coord: &Coord| coord.row() * self.columns + coord.column();
+        let mut regions = DisjointSet::initialize(self.rows * self.columns);
+        let mut walls = Vec::new();
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let coord = Coord::from(row, column);
+                let cell = self.grid[row][column].borrow();
+                for link in &cell.links {
+                    regions.union(index(&coord), index(link));
+                }
+                if cell.east.is_some() {
+                    walls.push((coord.clone(), Coord::from(row, column + 1)));
+                }
+                if cell.south.is_some() {
+                    walls.push((coord.clone(), Coord::from(row + 1, column)));
+                }
+            }
+        }
+
+        walls.shuffle(rng);
+        let mut carved = 0;
+        for (left, right) in walls {
+            if regions.union(index(&left), index(&right)) {
+                self.link(&left, &right);
+                carved += 1;
+            }
+        }
+        carved
+    }
+
     pub fn deadends(&self) -> usize {
         self.grid
             .iter()
             .map(|row| row.iter().filter(|col| col.borrow().links.len() == 1))
             .fold(0, |acc, x| acc + x.count())
     }
+
+    // Links dead ends to a neighbour with probability `p`, returning the number of loops added
+    pub fn braid<R: Rng>(&self, p: f64, rng: &mut R) -> usize {
+        let mut deadends = Vec::new();
+        for row in &self.grid {
+            for cell in row {
+                if cell.borrow().links.len() == 1 {
+                    deadends.push(cell.clone());
+                }
+            }
+        }
+        deadends.shuffle(rng);
+
+        let mut loops = 0;
+        for cell in deadends {
+            if cell.borrow().links.len() != 1 || !rng.gen_bool(p.clamp(0.0, 1.0)) {
+                continue;
+            }
+            let neighbours = cell
+                .borrow()
+                .neighbours()
+                .into_iter()
+                .map(|weak_ref| weak_ref.upgrade().unwrap())
+                .filter(|neighbour| {
+                    let neighbour = neighbour.borrow();
+                    !cell
+                        .borrow()
+                        .is_linked(&Coord::from(neighbour.row, neighbour.column))
+                })
+                .collect::<Vec<_>>();
+            let best = neighbours
+                .iter()
+                .filter(|neighbour| neighbour.borrow().links.len() == 1)
+                .cloned()
+                .collect::<Vec<_>>();
+            let candidates = if best.is_empty() { neighbours } else { best };
+            if let Some(neighbour) = candidates.choose(rng) {
+                cell::GridCell::link(&mut cell.borrow_mut(), &mut neighbour.borrow_mut());
+                loops += 1;
+            }
+        }
+        loops
+    }
+
+    // Deactivates dead ends until `solid_fraction` of the grid is solid, returning the number removed
+    pub fn cull_deadends(&mut self, solid_fraction: f64) -> usize {
+        let total = self.rows * self.columns;
+        let target = (solid_fraction.clamp(0.0, 1.0) * total as f64).ceil() as usize;
+        let mut solid = total - self.size();
+        let mut removed = 0;
+        while solid < target {
+            let mut deadends = Vec::new();
+            for row in &self.grid {
+                for cell in row {
+                    let cell = cell.borrow();
+                    if cell.links.len() == 1 {
+                        deadends.push(Coord::from(cell.row, cell.column));
+                    }
+                }
+            }
+            if deadends.is_empty() {
+                break;
+            }
+            deadends.shuffle(&mut rand::thread_rng());
+            for coord in deadends {
+                if solid >= target {
+                    break;
+                }
+                if self.grid[coord.row()][coord.column()].borrow().links.len() == 1 {
+                    self.deactivate(&coord);
+                    solid += 1;
+                    removed += 1;
+                }
+            }
+        }
+        removed
+    }
+
+    // A corridor is a straight run of linked cells, measured in cells
+    pub fn mean_corridor_length(&self) -> f64 {
+        let mut corridors = 0;
+        let mut total_length = 0;
+        for row in 0..self.rows {
+            let mut run = 0;
+            for column in 0..self.columns {
+                if self.grid[row][column]
+                    .borrow()
+                    .is_linked(&Coord::from(row, column + 1))
+                {
+                    run += 1;
+                } else if run > 0 {
+                    corridors += 1;
+                    total_length += run + 1;
+                    run = 0;
+                }
+            }
+        }
+        for column in 0..self.columns {
+            let mut run = 0;
+            for row in 0..self.rows {
+                if self.grid[row][column]
+                    .borrow()
+                    .is_linked(&Coord::from(row + 1, column))
+                {
+                    run += 1;
+                } else if run > 0 {
+                    corridors += 1;
+                    total_length += run + 1;
+                    run = 0;
+                }
+            }
+        }
+        if corridors == 0 {
+            0.0
+        } else {
+            total_length as f64 / corridors as f64
+        }
+    }
+}
+
+impl Grid {
+    // Corners sit between rows and columns, so the one at (row, column) touches up to four cells
+    fn corner(&self, row: usize, column: usize) -> &str {
+        let rows = [row.checked_sub(1), Some(row)];
+        let columns = [column.checked_sub(1), Some(column)];
+        let touches_active = rows.iter().flatten().any(|row| {
+            columns
+                .iter()
+                .flatten()
+                .any(|column| self.is_active(*row, *column))
+        });
+        if touches_active {
+            "+"
+        } else {
+            " "
+        }
+    }
+}
+
+impl Topology for Grid {
+    fn capacity(&self) -> usize {
+        self.rows * self.columns
+    }
+
+    fn index(&self, coord: &Coord) -> usize {
+        coord.row() * self.columns + coord.column()
+    }
+
+    fn coord(&self, index: usize) -> Coord {
+        Coord::from(index / self.columns, index % self.columns)
+    }
+
+    fn cells(&self) -> Vec<Coord> {
+        (0..self.capacity())
+            .map(|index| self.coord(index))
+            .filter(|coord| self.is_active(coord.row(), coord.column()))
+            .collect()
+    }
+
+    fn neighbours(&self, coord: &Coord) -> Vec<Coord> {
+        self.grid[coord.row()][coord.column()]
+            .borrow()
+            .neighbours()
+            .into_iter()
+            .map(|weak_ref| {
+                let neighbour = weak_ref.upgrade().unwrap();
+                let neighbour = neighbour.borrow();
+                Coord::from(neighbour.row, neighbour.column)
+            })
+            .collect()
+    }
+
+    fn links(&self, coord: &Coord) -> Vec<Coord> {
+        self.grid[coord.row()][coord.column()].borrow().get_links()
+    }
+
+    fn link(&self, from: &Coord, to: &Coord) {
+        Grid::link(self, from, to)
+    }
+
+    fn size(&self) -> usize {
+        Grid::size(self)
+    }
+
+    fn random_cell<R: Rng>(&self, rng: &mut R) -> Coord {
+        self.mask
+            .random_location(rng)
+            .expect("The mask has no cells that are on")
+    }
+
+    fn deadends(&self) -> usize {
+        Grid::deadends(self)
+    }
+
+    fn find_distances(&self, start: Coord) -> Distances {
+        Grid::find_distances(self, start)
+    }
 }
 
 impl ToString for Grid {
     fn to_string(&self) -> String {
         let mut output = String::new();
-        let section = "+".to_owned() + &"---+".repeat(self.columns) + "\n";
+        let section = self.corner(0, 0).to_owned()
+            + &(0..self.columns)
+                .map(|column| {
+                    let wall = if self.is_active(0, column) {
+                        "---"
+                    } else {
+                        "   "
+                    };
+                    wall.to_owned() + self.corner(0, column + 1)
+                })
+                .collect::<String>()
+            + "\n";
         output.push_str(&section);
         self.grid
             .iter()
-            .map(|row| {
-                let top = "|".to_owned();
-                let bottom = "+".to_owned();
+            .enumerate()
+            .map(|(row_index, row)| {
+                let top = if self.is_active(row_index, 0) {
+                    "|".to_owned()
+                } else {
+                    " ".to_owned()
+                };
+                let bottom = self.corner(row_index + 1, 0).to_owned();
                 let (top, bottom) = row.iter().fold((top, bottom), |acc, cell| {
-                    let body = self.contents_of(&cell.borrow());
-                    let east_boundary = if let Some(eastern_neighbour) = &cell.borrow().east {
-                        let eastern_cell = eastern_neighbour.upgrade().unwrap();
-                        let east_coords =
-                            Coord::from(eastern_cell.borrow().row, eastern_cell.borrow().column);
-                        if cell.borrow().is_linked(&east_coords) {
-                            " "
-                        } else {
-                            "|"
-                        }
+                    let cell = cell.borrow();
+                    let coords = Coord::from(cell.row, cell.column);
+                    let body = if self.is_active(cell.row, cell.column) {
+                        self.contents_of(&cell)
                     } else {
-                        "|"
+                        "   ".to_owned()
                     };
-                    let south_boundary = if let Some(southern_neighbour) = &cell.borrow().south {
-                        let southern_cell = southern_neighbour.upgrade().unwrap();
-                        let south_coords =
-                            Coord::from(southern_cell.borrow().row, southern_cell.borrow().column);
-                        if cell.borrow().is_linked(&south_coords) {
-                            "   "
+                    let east_boundary =
+                        if self.has_wall(&coords, &Coord::from(cell.row, cell.column + 1)) {
+                            "|"
                         } else {
+                            " "
+                        };
+                    let south_boundary =
+                        if self.has_wall(&coords, &Coord::from(cell.row + 1, cell.column)) {
                             "---"
-                        }
-                    } else {
-                        "---"
-                    };
-                    (acc.0 + &body + east_boundary, acc.1 + south_boundary + "+")
+                        } else {
+                            "   "
+                        };
+                    let corner = self.corner(cell.row + 1, cell.column + 1);
+                    (
+                        acc.0 + &body + east_boundary,
+                        acc.1 + south_boundary + corner,
+                    )
                 });
                 (top, bottom)
             })
@@ -228,6 +603,11 @@ impl ToString for Grid {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::binary_tree::BinaryTree;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+    use crate::side_winder::SideWinder;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     #[test]
     fn initializes_grid() {
@@ -303,6 +683,165 @@ mod tests {
         assert_eq!((1, 3), (cell_ref.borrow().row, cell_ref.borrow().column));
     }
 
+    #[test]
+    fn links_and_unlinks_by_coord() {
+        let mut grid = Grid::initialize(2, 2);
+        grid.configure_cells();
+        grid.link(&Coord::from(0, 0), &Coord::from(0, 1));
+        assert!(grid.grid[0][0].borrow().is_linked(&Coord::from(0, 1)));
+        assert!(grid.grid[0][1].borrow().is_linked(&Coord::from(0, 0)));
+        grid.unlink(&Coord::from(0, 1), &Coord::from(0, 0));
+        assert!(grid.grid[0][0].borrow().links.is_empty());
+        assert!(grid.grid[0][1].borrow().links.is_empty());
+    }
+
+    #[test]
+    fn measures_mean_corridor_length() {
+        let mut grid = Grid::initialize(2, 3);
+        grid.configure_cells();
+        assert_eq!(0.0, grid.mean_corridor_length());
+        grid.link(&Coord::from(0, 0), &Coord::from(0, 1));
+        grid.link(&Coord::from(0, 1), &Coord::from(0, 2));
+        grid.link(&Coord::from(0, 2), &Coord::from(1, 2));
+        assert_eq!(2.5, grid.mean_corridor_length());
+    }
+
+    #[test]
+    fn braids_away_deadends() {
+        let mut grid = Grid::initialize(3, 3);
+        grid.configure_cells();
+        for row in 0..3 {
+            grid.link(&Coord::from(row, 0), &Coord::from(row, 1));
+            grid.link(&Coord::from(row, 1), &Coord::from(row, 2));
+        }
+        grid.link(&Coord::from(0, 2), &Coord::from(1, 2));
+        grid.link(&Coord::from(1, 0), &Coord::from(2, 0));
+        assert_eq!(2, grid.deadends());
+
+        let mut rng = rand::thread_rng();
+        assert_eq!(0, grid.braid(0.0, &mut rng));
+        assert!(grid.braid(1.0, &mut rng) > 0);
+        assert_eq!(0, grid.deadends());
+
+        let distances = grid.find_distances(Coord::from(0, 0));
+        assert_eq!(9, distances.cells.len());
+        assert_eq!(1, distances.get_cell_distance(&Coord::from(1, 0)));
+        grid.longest_path();
+    }
+
+    #[test]
+    fn braids_all_or_nothing_at_the_extremes() {
+        for seed in 0..10 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut grid = Grid::initialize(12, 12);
+            grid.configure_cells();
+            RecursiveBacktracker::on_with_rng(&grid, &mut rng);
+            let deadends = grid.deadends();
+            assert_eq!(0, grid.braid(0.0, &mut rng));
+            assert_eq!(deadends, grid.deadends());
+            assert!(grid.braid(1.0, &mut rng) > 0);
+            assert_eq!(0, grid.deadends());
+        }
+    }
+
+    #[test]
+    fn culls_deadends_into_inactive_cells() {
+        let mut grid = Grid::initialize(3, 3);
+        grid.configure_cells();
+        for row in 0..3 {
+            grid.link(&Coord::from(row, 0), &Coord::from(row, 1));
+            grid.link(&Coord::from(row, 1), &Coord::from(row, 2));
+        }
+        grid.link(&Coord::from(0, 2), &Coord::from(1, 2));
+        grid.link(&Coord::from(1, 0), &Coord::from(2, 0));
+
+        assert_eq!(5, grid.cull_deadends(0.5));
+        assert_eq!(4, grid.size());
+        let start = grid.mask.first_location().unwrap();
+        assert_eq!(4, grid.find_distances(start).cells.len());
+        for row in 0..3 {
+            for column in 0..3 {
+                let cell = grid.grid[row][column].borrow();
+                if !grid.is_active(row, column) {
+                    assert!(cell.links.is_empty());
+                    assert!(cell.neighbours().is_empty());
+                }
+            }
+        }
+        grid.longest_path();
+    }
+
+    #[test]
+    fn walls_stand_only_next_to_active_cells() {
+        let mut grid = Grid::initialize(1, 3);
+        grid.configure_cells();
+        grid.link(&Coord::from(0, 0), &Coord::from(0, 1));
+        grid.deactivate(&Coord::from(0, 2));
+        assert!(!grid.has_wall(&Coord::from(0, 0), &Coord::from(0, 1)));
+        assert!(grid.has_wall(&Coord::from(0, 1), &Coord::from(0, 2)));
+        assert!(!grid.has_wall(&Coord::from(0, 2), &Coord::from(0, 3)));
+        assert!(grid.has_wall(&Coord::from(0, 0), &Coord::from(1, 0)));
+        assert!(grid.grid[0][1].borrow().east.is_none());
+        assert_eq!(
+            "+---+---+    \n| 0     |    \n+---+---+    \n",
+            grid.to_string()
+        );
+    }
+
+    #[test]
+    fn generators_stay_inside_the_mask() {
+        let template = "....X\n.XX..\n.X...\n...X.\nX....\n";
+        let generators: [fn(&Grid); 2] = [BinaryTree::on, SideWinder::on];
+        for generator in generators.iter() {
+            let grid = Grid::from_mask(Mask::from_txt(template).unwrap()).unwrap();
+            assert_eq!(19, grid.size());
+            generator(&grid);
+            assert_eq!(Ok(()), grid.validate());
+            assert!(grid.grid[1][1].borrow().links.is_empty());
+            assert!(grid.is_perfect());
+        }
+
+        let mut mask = Mask::initialize(1, 3);
+        mask.set(0, 1, false);
+        assert!(matches!(
+            Grid::from_mask(mask),
+            Err(MaskError::Disconnected)
+        ));
+    }
+
+    #[test]
+    fn validates_links_and_connectivity() {
+        let mut grid = Grid::initialize(1, 3);
+        grid.configure_cells();
+        grid.link(&Coord::from(0, 0), &Coord::from(0, 1));
+        assert_eq!(
+            Err(GridError::Disconnected {
+                reached: 2,
+                size: 3
+            }),
+            grid.validate()
+        );
+        grid.link(&Coord::from(0, 1), &Coord::from(0, 2));
+        assert_eq!(Ok(()), grid.validate());
+        grid.grid[0][2].borrow_mut().links.insert(Coord::from(0, 0));
+        assert_eq!(
+            Err(GridError::NotNeighbours(
+                Coord::from(0, 2),
+                Coord::from(0, 0)
+            )),
+            grid.validate()
+        );
+        grid.deactivate(&Coord::from(0, 2));
+        grid.grid[0][0]
+            .borrow_mut()
+            .links
+            .remove(&Coord::from(0, 1));
+        assert_eq!(
+            Err(GridError::OneWayLink(Coord::from(0, 1), Coord::from(0, 0))),
+            grid.validate()
+        );
+    }
+
     #[test]
     fn random_cell_exists() {
         let mut grid = Grid::initialize(5, 5);
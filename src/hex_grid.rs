@@ -0,0 +1,187 @@
+use std::fmt;
+
+use crate::cell::Coord;
+use crate::distances::Distances;
+use crate::topology::{Passages, Shape, Topology};
+
+// A grid of flat-topped hexagons. Columns are offset, with every odd column sitting half a
+// cell lower than its even neighbours, so a cell's diagonal neighbours depend on its column.
+#[derive(Debug)]
+pub struct HexGrid {
+    pub rows: usize,
+    pub columns: usize,
+    passages: Passages,
+    pub distances: Distances,
+}
+
+impl HexGrid {
+    pub fn initialize(rows: usize, columns: usize) -> HexGrid {
+        HexGrid {
+            rows,
+            columns,
+            passages: Passages::rectangle(rows, columns),
+            distances: Distances::initialize(Coord::from(0, 0)),
+        }
+    }
+
+    fn at(&self, row: isize, column: isize) -> Option<Coord> {
+        if row < 0 || column < 0 || row as usize >= self.rows || column as usize >= self.columns {
+            return None;
+        }
+        Some(Coord::from(row as usize, column as usize))
+    }
+
+    // The rows of the diagonal neighbours to the north and to the south of a cell
+    fn diagonal_rows(&self, coord: &Coord) -> (isize, isize) {
+        let row = coord.row() as isize;
+        if coord.column().is_multiple_of(2) {
+            (row - 1, row)
+        } else {
+            (row, row + 1)
+        }
+    }
+
+    pub fn north(&self, coord: &Coord) -> Option<Coord> {
+        self.at(coord.row() as isize - 1, coord.column() as isize)
+    }
+
+    pub fn south(&self, coord: &Coord) -> Option<Coord> {
+        self.at(coord.row() as isize + 1, coord.column() as isize)
+    }
+
+    pub fn northeast(&self, coord: &Coord) -> Option<Coord> {
+        self.at(self.diagonal_rows(coord).0, coord.column() as isize + 1)
+    }
+
+    pub fn northwest(&self, coord: &Coord) -> Option<Coord> {
+        self.at(self.diagonal_rows(coord).0, coord.column() as isize - 1)
+    }
+
+    pub fn southeast(&self, coord: &Coord) -> Option<Coord> {
+        self.at(self.diagonal_rows(coord).1, coord.column() as isize + 1)
+    }
+
+    pub fn southwest(&self, coord: &Coord) -> Option<Coord> {
+        self.at(self.diagonal_rows(coord).1, coord.column() as isize - 1)
+    }
+
+    fn has_wall(&self, coord: &Coord, neighbour: Option<Coord>) -> bool {
+        !neighbour.is_some_and(|neighbour| self.is_linked(coord, &neighbour))
+    }
+
+    fn contents_of(&self, coord: &Coord) -> char {
+        self.distances
+            .cells
+            .get(coord)
+            .map(|x| std::char::from_digit(*x as u32, 36).unwrap_or('~'))
+            .unwrap_or(' ')
+    }
+}
+
+impl Shape for HexGrid {
+    fn passages(&self) -> &Passages {
+        &self.passages
+    }
+
+    fn adjacent(&self, coord: &Coord) -> Vec<Coord> {
+        vec![
+            self.north(coord),
+            self.northeast(coord),
+            self.southeast(coord),
+            self.south(coord),
+            self.southwest(coord),
+            self.northwest(coord),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// Each hexagon is drawn as
+//  __
+// /d \
+// \__/
+// with neighbouring cells sharing their edges. A wall is drawn by both of its cells, so a
+// passage is only left open when neither of them draws it.
+impl fmt::Display for HexGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let height = 2 * self.rows + 2;
+        let width = 3 * self.columns + 1;
+        let mut canvas = vec![vec![' '; width]; height];
+        for coord in self.cells() {
+            let x = 3 * coord.column();
+            let y = 2 * coord.row() + coord.column() % 2;
+            canvas[y + 1][x + 1] = self.contents_of(&coord);
+            if self.has_wall(&coord, self.north(&coord)) {
+                canvas[y][x + 1] = '_';
+                canvas[y][x + 2] = '_';
+            }
+            if self.has_wall(&coord, self.south(&coord)) {
+                canvas[y + 2][x + 1] = '_';
+                canvas[y + 2][x + 2] = '_';
+            }
+            if self.has_wall(&coord, self.northwest(&coord)) {
+                canvas[y + 1][x] = '/';
+            }
+            if self.has_wall(&coord, self.northeast(&coord)) {
+                canvas[y + 1][x + 3] = '\\';
+            }
+            if self.has_wall(&coord, self.southwest(&coord)) {
+                canvas[y + 2][x] = '\\';
+            }
+            if self.has_wall(&coord, self.southeast(&coord)) {
+                canvas[y + 2][x + 3] = '/';
+            }
+        }
+        for line in canvas {
+            writeln!(f, "{}", line.iter().collect::<String>().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+
+    #[test]
+    fn finds_six_neighbours_across_offset_columns() {
+        let grid = HexGrid::initialize(4, 4);
+        assert_eq!(
+            vec![
+                Coord::from(0, 1),
+                Coord::from(1, 2),
+                Coord::from(2, 2),
+                Coord::from(2, 1),
+                Coord::from(2, 0),
+                Coord::from(1, 0),
+            ],
+            grid.neighbours(&Coord::from(1, 1))
+        );
+        assert_eq!(
+            vec![
+                Coord::from(0, 2),
+                Coord::from(0, 3),
+                Coord::from(1, 3),
+                Coord::from(2, 2),
+                Coord::from(1, 1),
+                Coord::from(0, 1),
+            ],
+            grid.neighbours(&Coord::from(1, 2))
+        );
+        assert_eq!(2, grid.neighbours(&Coord::from(0, 0)).len());
+    }
+
+    #[test]
+    fn carves_a_perfect_hex_maze() {
+        let mut grid = HexGrid::initialize(5, 6);
+        RecursiveBacktracker::on(&grid);
+        assert!(grid.is_perfect());
+        grid.distances = grid.find_distances(Coord::from(0, 0));
+        let text = grid.to_string();
+        assert_eq!(12, text.lines().count());
+        assert!(text.starts_with(" __    __    __\n/0"));
+    }
+}
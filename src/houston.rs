@@ -0,0 +1,83 @@
+use rand::Rng;
+
+use crate::cell::{Coord, GridCell};
+use crate::grid::Grid;
+use crate::wilsons::Wilsons;
+
+pub struct Houston {}
+
+#[derive(Debug)]
+pub struct HoustonSteps {
+    pub aldous_broder: usize,
+    pub wilsons: usize,
+}
+
+impl Houston {
+    pub fn on(grid: &Grid, switch_fraction: f64) -> HoustonSteps {
+        Houston::on_with_rng(grid, switch_fraction, &mut rand::thread_rng())
+    }
+
+    pub fn on_with_rng<R: Rng>(grid: &Grid, switch_fraction: f64, rng: &mut R) -> HoustonSteps {
+        let start = grid.get_random_cell_with(rng).upgrade().unwrap();
+        let start_coord = Coord::from(start.borrow().row, start.borrow().column);
+        let target = (switch_fraction.clamp(0.0, 1.0) * grid.size() as f64).ceil() as usize;
+        let mut cell_ref = start;
+        let mut visited = 1;
+        let mut aldous_broder = 0;
+        while visited < target {
+            let mut neighbours = cell_ref.borrow().neighbours();
+            let rand_num = rng.gen_range(0, neighbours.len());
+            let rand_neighbour_ref = neighbours.remove(rand_num).upgrade().unwrap();
+            if rand_neighbour_ref.borrow().get_links().is_empty() {
+                GridCell::link(
+                    &mut cell_ref.borrow_mut(),
+                    &mut rand_neighbour_ref.borrow_mut(),
+                );
+                visited += 1;
+            }
+            cell_ref = rand_neighbour_ref;
+            aldous_broder += 1;
+        }
+
+        let mut unvisited = Vec::new();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                let coord = Coord::from(row, column);
+                if coord != start_coord
+                    && grid.is_active(row, column)
+                    && grid.grid[row][column].borrow().links.is_empty()
+                {
+                    unvisited.push(coord);
+                }
+            }
+        }
+        let wilsons = Wilsons::walk_from(grid, unvisited, rng);
+        HoustonSteps {
+            aldous_broder,
+            wilsons,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn carves_a_spanning_tree_at_any_switch_point() {
+        for (seed, switch_fraction) in [0.0, 0.3, 0.7, 1.0].iter().enumerate() {
+            let mut grid = Grid::initialize(9, 14);
+            grid.configure_cells();
+            let mut rng = StdRng::seed_from_u64(seed as u64);
+            let steps = Houston::on_with_rng(&grid, *switch_fraction, &mut rng);
+            assert_eq!(Ok(()), grid.validate());
+            assert!(grid.is_perfect());
+            if *switch_fraction == 1.0 {
+                assert_eq!(0, steps.wilsons);
+            }
+        }
+    }
+}
@@ -1,53 +1,105 @@
-use rand;
+use std::collections::BTreeSet;
 
-use crate::cell::{GridCell, GridCellRef};
-use crate::grid::Grid;
+use rand::Rng;
+
+use crate::cell::Coord;
+use crate::topology::Topology;
 
 pub struct HuntAndKill {}
 
 impl HuntAndKill {
-    pub fn on(grid: &Grid) {
-        let mut current = grid.get_random_cell().upgrade();
-        'visiting: while current.is_some() {
-            let unvisited_neighbours: Vec<GridCellRef> = current
-                .as_ref()
-                .unwrap()
-                .borrow()
-                .neighbours()
+    pub fn on<T: Topology>(grid: &T) {
+        HuntAndKill::on_with_rng(grid, &mut rand::thread_rng());
+    }
+
+    pub fn on_with_rng<T: Topology, R: Rng>(grid: &T, rng: &mut R) {
+        let mut visited = vec![false; grid.capacity()];
+        // Unvisited cells next to a visited one, ordered the way the hunt scans the grid
+        let mut frontier = BTreeSet::new();
+        let mut current = grid.random_cell(rng);
+        visit(grid, &current, &mut visited, &mut frontier);
+        loop {
+            let unvisited_neighbours: Vec<Coord> = grid
+                .neighbours(&current)
                 .into_iter()
-                .map(|weak_ref| weak_ref.upgrade().unwrap())
-                .filter(|cell_ref| cell_ref.borrow().links.is_empty())
+                .filter(|neighbour| !visited[grid.index(neighbour)])
                 .collect();
             if unvisited_neighbours.is_empty() {
-                current = None;
-                'hunting: for row in 0..grid.rows {
-                    for col in 0..grid.columns {
-                        let cell = &grid.grid[row][col];
-                        let visited_neighbours: Vec<GridCellRef> = cell
-                            .borrow()
-                            .neighbours()
-                            .into_iter()
-                            .map(|weak_ref| weak_ref.upgrade().unwrap())
-                            .filter(|cell_ref| !cell_ref.borrow().links.is_empty())
-                            .collect();
-                        if cell.borrow().links.is_empty() && !visited_neighbours.is_empty() {
-                            let rand_num = rand::random::<usize>() % visited_neighbours.len();
-                            let neighbour = visited_neighbours.get(rand_num).unwrap();
-                            GridCell::link(&mut cell.borrow_mut(), &mut neighbour.borrow_mut());
-                            current = Some(cell.clone());
-                            break 'hunting;
-                        }
-                    }
-                }
+                let index = match frontier.iter().next() {
+                    Some(index) => *index,
+                    None => break,
+                };
+                let cell = grid.coord(index);
+                let visited_neighbours: Vec<Coord> = grid
+                    .neighbours(&cell)
+                    .into_iter()
+                    .filter(|neighbour| visited[grid.index(neighbour)])
+                    .collect();
+                let rand_num = rng.gen_range(0, visited_neighbours.len());
+                grid.link(&cell, &visited_neighbours[rand_num]);
+                visit(grid, &cell, &mut visited, &mut frontier);
+                current = cell;
             } else {
-                let rand_num = rand::random::<usize>() % unvisited_neighbours.len();
-                let neighbour = unvisited_neighbours.get(rand_num).unwrap();
-                GridCell::link(
-                    &mut current.unwrap().borrow_mut(),
-                    &mut neighbour.borrow_mut(),
-                );
-                current = Some(neighbour.clone());
+                let rand_num = rng.gen_range(0, unvisited_neighbours.len());
+                let neighbour = &unvisited_neighbours[rand_num];
+                grid.link(&current, neighbour);
+                visit(grid, neighbour, &mut visited, &mut frontier);
+                current = neighbour.clone();
             }
         }
     }
 }
+
+fn visit<T: Topology>(
+    grid: &T,
+    coord: &Coord,
+    visited: &mut [bool],
+    frontier: &mut BTreeSet<usize>,
+) {
+    let index = grid.index(coord);
+    visited[index] = true;
+    frontier.remove(&index);
+    for neighbour in grid.neighbours(coord) {
+        let neighbour_index = grid.index(&neighbour);
+        if !visited[neighbour_index] {
+            frontier.insert(neighbour_index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn carve(rows: usize, columns: usize, seed: u64) -> Grid {
+        let mut grid = Grid::initialize(rows, columns);
+        grid.configure_cells();
+        HuntAndKill::on_with_rng(&grid, &mut StdRng::seed_from_u64(seed));
+        grid
+    }
+
+    #[test]
+    fn repeats_a_maze_from_its_seed() {
+        let first = carve(15, 20, 7);
+        let second = carve(15, 20, 7);
+        assert!(first.is_perfect());
+        for coord in first.cells() {
+            let cell = first.grid[coord.row()][coord.column()].borrow();
+            assert_eq!(
+                cell.links,
+                second.grid[coord.row()][coord.column()].borrow().links
+            );
+        }
+    }
+
+    // Takes a few seconds in a release build
+    #[test]
+    #[ignore]
+    fn carves_a_million_cells() {
+        let grid = carve(1000, 1000, 11);
+        assert!(grid.is_perfect());
+    }
+}
@@ -1,12 +1,36 @@
 mod aldous_broder;
 mod binary_tree;
+mod blended;
 mod cell;
+mod cellular_automaton;
+mod constrained;
+mod cost_field;
+mod disjoint_set;
 mod distances;
+mod dungeon;
+mod fractal;
 mod grid;
+mod hex_grid;
+mod houston;
 mod hunt_and_kill;
+mod mask;
+mod origin_shift;
+mod polar_grid;
+mod preplaced;
 mod recursive_backtracker;
 mod render;
 mod side_winder;
+mod solution_path;
+mod symmetric;
+mod topology;
+mod triangle_grid;
+mod unicursal;
+mod uniformity;
+mod upsilon_grid;
+mod weave_grid;
+mod weave_kruskals;
+mod weighted_kruskals;
+mod weighted_prims;
 mod wilsons;
 
 use cell::Coord;
@@ -67,6 +91,42 @@ fn main() {
         );
         grid.to_png("sidewinder.png");
     }
+    {
+        use binary_tree::Diagonal;
+        for (diagonal, name) in &[
+            (Diagonal::NorthEast, "ne"),
+            (Diagonal::NorthWest, "nw"),
+            (Diagonal::SouthEast, "se"),
+            (Diagonal::SouthWest, "sw"),
+        ] {
+            grid.reset();
+            binary_tree::BinaryTree::on_biased(&grid, *diagonal, 0.75, &mut rand::thread_rng());
+            grid.to_png(&format!("binary_tree_{}.png", name));
+        }
+        println!(
+            "Here's a binary tree maze biased to the {:?} with mostly vertical passages\n{}",
+            Diagonal::SouthWest,
+            grid.to_string()
+        );
+    }
+    {
+        use cell::Side;
+        for (side, name) in &[
+            (Side::North, "north"),
+            (Side::South, "south"),
+            (Side::East, "east"),
+            (Side::West, "west"),
+        ] {
+            grid.reset();
+            side_winder::SideWinder::on_biased(&grid, *side, 0.2, &mut rand::thread_rng());
+            grid.to_png(&format!("sidewinder_{}.png", name));
+        }
+        println!(
+            "Here's a sidewinder maze with long runs closed out to the {:?}\n{}",
+            Side::West,
+            grid.to_string()
+        );
+    }
     grid.reset();
     {
         aldous_broder::AldousBroder::on(&grid);
@@ -167,6 +227,576 @@ fn main() {
         );
         grid.to_png("recursive_backtracker.png");
     }
+    for straightness in &[0.0, 0.5, 1.0] {
+        grid.reset();
+        recursive_backtracker::RecursiveBacktracker::on_with_straightness(
+            &grid,
+            *straightness,
+            &mut rand::thread_rng(),
+        );
+        println!(
+            "Here's a recursive backtracker maze with straightness {} (mean corridor length {:.2})\n{}",
+            straightness,
+            grid.mean_corridor_length(),
+            grid.to_string()
+        );
+        grid.to_png(&format!(
+            "recursive_backtracker_straightness_{}.png",
+            (straightness * 100.0) as usize
+        ));
+    }
+    grid.reset();
+    {
+        let steps = houston::Houston::on(&grid, 0.5);
+        stats.push(format!(
+            "Houston: {}/{} ({}%)",
+            grid.deadends(),
+            grid.size(),
+            (grid.deadends() * 100) / grid.size()
+        ));
+        println!(
+            "Here's the maze using the Houston algorithm ({} Aldous-Broder steps, {} Wilson's steps)!\n{}",
+            steps.aldous_broder,
+            steps.wilsons,
+            grid.to_string()
+        );
+        grid.distances = grid.longest_path();
+        println!(
+            "Here's the most difficult path in the maze\n{}",
+            grid.to_string()
+        );
+        grid.to_png("houston.png");
+    }
+    grid.reset();
+    {
+        let mut origin_shift = origin_shift::OriginShift::on(&grid, grid.size() * 10);
+        stats.push(format!(
+            "Origin Shift: {}/{} ({}%)",
+            grid.deadends(),
+            grid.size(),
+            (grid.deadends() * 100) / grid.size()
+        ));
+        println!(
+            "Here's the maze using the Origin Shift algorithm!\n{}",
+            grid.to_string()
+        );
+        grid.to_png("origin_shift.png");
+        let shifts = (0..grid.size())
+            .filter_map(|_| origin_shift.step(&grid))
+            .collect::<Vec<_>>();
+        if let Some(shift) = shifts.last() {
+            println!(
+                "After {} more shifts the root is at {:?}; the last one opened {:?} and closed {:?}\n{}",
+                shifts.len(),
+                origin_shift.root,
+                shift.opened,
+                shift.closed,
+                grid.to_string()
+            );
+        }
+    }
+    grid.reset();
+    {
+        recursive_backtracker::RecursiveBacktracker::on(&grid);
+        let mut origin_shift = origin_shift::OriginShift::rooted(&grid, Coord::from(0, 0))
+            .expect("The recursive backtracker always carves a perfect maze");
+        for _ in 0..grid.size() {
+            origin_shift.step(&grid);
+        }
+        println!(
+            "Here's a recursive backtracker maze after {} origin shifts\n{}",
+            grid.size(),
+            grid.to_string()
+        );
+    }
+    grid.reset();
+    {
+        let rule = cellular_automaton::Rule::parse(cellular_automaton::MAZE).unwrap();
+        cellular_automaton::CellularAutomaton::on(&grid, &rule, 20);
+        stats.push(format!(
+            "Cellular Automaton (Maze): {}/{} ({}%)",
+            grid.deadends(),
+            grid.size(),
+            (grid.deadends() * 100) / grid.size()
+        ));
+        println!(
+            "Here's the maze using the Maze cellular automaton!\n{}",
+            grid.to_string()
+        );
+        grid.to_png("cellular_automaton_maze.png");
+    }
+    grid.reset();
+    {
+        let rule = cellular_automaton::Rule::parse(cellular_automaton::MAZECTRIC).unwrap();
+        cellular_automaton::CellularAutomaton::on(&grid, &rule, 20);
+        stats.push(format!(
+            "Cellular Automaton (Mazectric): {}/{} ({}%)",
+            grid.deadends(),
+            grid.size(),
+            (grid.deadends() * 100) / grid.size()
+        ));
+        println!(
+            "Here's the maze using the Mazectric cellular automaton!\n{}",
+            grid.to_string()
+        );
+        grid.to_png("cellular_automaton_mazectric.png");
+    }
+    grid.reset();
+    {
+        recursive_backtracker::RecursiveBacktracker::on(&grid);
+        let deadends = grid.deadends();
+        let loops = grid.braid(0.5, &mut rand::thread_rng());
+        println!(
+            "Here's a recursive backtracker maze braided from {} to {} deadends with {} loops\n{}",
+            deadends,
+            grid.deadends(),
+            loops,
+            grid.to_string()
+        );
+        grid.distances = grid.longest_path();
+        grid.to_png("braided.png");
+    }
+    {
+        let mut sparse_grid = Grid::initialize(grid.rows, grid.columns);
+        sparse_grid.configure_cells();
+        recursive_backtracker::RecursiveBacktracker::on(&sparse_grid);
+        let removed = sparse_grid.cull_deadends(0.6);
+        println!(
+            "Here's a sparse recursive backtracker maze with {} cells culled into rock\n{}",
+            removed,
+            sparse_grid.to_string()
+        );
+        sparse_grid.distances = sparse_grid.longest_path();
+        sparse_grid.to_png("sparse.png");
+    }
+    {
+        let mut dungeon_grid = Grid::initialize(30, 30);
+        dungeon_grid.configure_cells();
+        let options = dungeon::DungeonOptions {
+            doors_per_room: 2,
+            cull: Some(0.3),
+            ..Default::default()
+        };
+        let dungeon = dungeon::Dungeon::on(
+            &mut dungeon_grid,
+            &options,
+            recursive_backtracker::RecursiveBacktracker::on,
+        );
+        let doors = dungeon
+            .tags
+            .iter()
+            .flatten()
+            .filter(|tag| **tag == dungeon::Tag::Door)
+            .count();
+        println!(
+            "Here's a dungeon with {} rooms and {} doors\n{}",
+            dungeon.rooms.len(),
+            doors,
+            dungeon_grid.to_string()
+        );
+        if let Some(room) = dungeon.rooms.first() {
+            println!(
+                "The first room is {}x{} at ({}, {}) and its corner is tagged {:?}",
+                room.height, room.width, room.row, room.column, dungeon.tags[room.row][room.column]
+            );
+        }
+        if dungeon
+            .tags
+            .iter()
+            .flatten()
+            .any(|tag| *tag == dungeon::Tag::Corridor)
+        {
+            dungeon_grid.distances = dungeon_grid.longest_path();
+        }
+        dungeon_grid.to_png("dungeon.png");
+    }
+    {
+        let mut constrained_grid = Grid::initialize(30, 30);
+        constrained_grid.configure_cells();
+        let constraints = constrained::Constraints {
+            start: Coord::from(0, 0),
+            goal: Coord::from(29, 29),
+            checks: vec![
+                Box::new(|metrics| metrics.solution_length.unwrap_or(0) >= 250),
+                Box::new(|metrics| metrics.deadend_ratio() <= 0.35),
+                Box::new(|metrics| metrics.longest_path >= 400),
+            ],
+            seeds: 20,
+            mutations_per_seed: 200,
+        };
+        let outcome = constrained::Constrained::on(
+            &mut constrained_grid,
+            recursive_backtracker::RecursiveBacktracker::on_with_rng,
+            &constraints,
+        );
+        match &outcome {
+            Ok(report) => println!(
+                "Found a constrained maze with seed {} after {} seeds and {} mutations: {:?}",
+                report.seed, report.attempts, report.mutations, report.metrics
+            ),
+            Err(report) => println!(
+                "Gave up after {} seeds and {} mutations, the best maze (seed {}) met {} of {} constraints: {:?}",
+                report.attempts,
+                report.mutations,
+                report.seed,
+                report.satisfied,
+                constraints.checks.len(),
+                report.metrics
+            ),
+        }
+        constrained_grid.distances = constrained_grid.find_distances(Coord::from(0, 0));
+        constrained_grid.distances = constrained_grid.path_to(Coord::from(29, 29));
+        println!("{}", constrained_grid.to_string());
+        constrained_grid.to_png("constrained.png");
+    }
+    {
+        use symmetric::Symmetry;
+        for (symmetry, name) in &[
+            (Symmetry::MirrorLeftRight, "mirror_left_right"),
+            (Symmetry::MirrorTopBottom, "mirror_top_bottom"),
+            (Symmetry::Rotate2, "rotate_2"),
+            (Symmetry::Rotate4, "rotate_4"),
+        ] {
+            let mut symmetric_grid = Grid::initialize(21, 21);
+            symmetric_grid.configure_cells();
+            let outcome = symmetric::Symmetric::on(
+                &mut symmetric_grid,
+                *symmetry,
+                true,
+                recursive_backtracker::RecursiveBacktracker::on,
+            );
+            println!(
+                "Here's a maze with {:?} symmetry ({:?}, validation: {:?})\n{}",
+                symmetry,
+                outcome,
+                symmetric_grid.validate(),
+                symmetric_grid.to_string()
+            );
+            symmetric_grid.to_png(&format!("symmetric_{}.png", name));
+        }
+    }
+    {
+        let mut perfect_grid = Grid::initialize(8, 8);
+        perfect_grid.configure_cells();
+        recursive_backtracker::RecursiveBacktracker::on(&perfect_grid);
+        if let Some(labyrinth) = unicursal::Unicursal::from(&perfect_grid) {
+            println!(
+                "Here's a unicursal labyrinth walked from an 8x8 maze\n{}",
+                labyrinth.to_string()
+            );
+            labyrinth.to_png("unicursal.png");
+            labyrinth.to_png_path("unicursal_path.png");
+        }
+    }
+    {
+        let template = "\
+            XXXX......XXXX\n\
+            XX..........XX\n\
+            X....XXXX....X\n\
+            ....XXXXXX....\n\
+            ....XXXXXX....\n\
+            X....XXXX....X\n\
+            XX..........XX\n\
+            XXXX......XXXX\n";
+        let mask = mask::Mask::from_txt(template).unwrap();
+        let masked_grid = Grid::from_mask(mask.clone()).unwrap();
+        recursive_backtracker::RecursiveBacktracker::on(&masked_grid);
+        println!(
+            "Here's a maze in the shape of a ring ({} cells)\n{}",
+            masked_grid.size(),
+            masked_grid.to_string()
+        );
+        masked_grid.to_png("masked.png");
+
+        let masked_grid = Grid::from_mask(mask).unwrap();
+        side_winder::SideWinder::on(&masked_grid);
+        println!(
+            "Here's the ring using the sidewinder algorithm\n{}",
+            masked_grid.to_string()
+        );
+
+        match mask::Mask::from_png("mask.png").and_then(Grid::from_mask) {
+            Ok(logo_grid) => {
+                wilsons::Wilsons::on(&logo_grid);
+                logo_grid.to_png("logo.png");
+            }
+            Err(mask::MaskError::Image(error)) => println!("Skipping the logo maze: {:?}", error),
+            Err(error) => println!("Skipping the logo maze: {:?}", error),
+        }
+    }
+    {
+        let mut fixtures = preplaced::Fixtures::default();
+        // A straight entrance hall from the top edge down to a ring around the centre
+        let hall: Vec<Coord> = (0..6).map(|row| Coord::from(row, 7)).collect();
+        fixtures.carve_path(&hall);
+        let mut ring: Vec<Coord> = (5..10).map(|column| Coord::from(5, column)).collect();
+        ring.extend((6..10).map(|row| Coord::from(row, 9)));
+        ring.extend((5..9).rev().map(|column| Coord::from(9, column)));
+        ring.extend((5..9).rev().map(|row| Coord::from(row, 5)));
+        fixtures.carve_path(&ring);
+        // The centre is fenced off from the ring apart from a doorway at the bottom
+        for inner in 6..9 {
+            fixtures
+                .walls
+                .push((Coord::from(5, inner), Coord::from(6, inner)));
+            fixtures
+                .walls
+                .push((Coord::from(inner, 9), Coord::from(inner, 8)));
+            fixtures
+                .walls
+                .push((Coord::from(inner, 5), Coord::from(inner, 6)));
+            if inner != 7 {
+                fixtures
+                    .walls
+                    .push((Coord::from(9, inner), Coord::from(8, inner)));
+            }
+        }
+
+        let mut fixed_grid = Grid::initialize(15, 15);
+        fixed_grid.configure_cells();
+        let outcome =
+            preplaced::Preplaced::on(&mut fixed_grid, &fixtures, hunt_and_kill::HuntAndKill::on);
+        println!(
+            "Here's a maze around a fixed hall and ring ({:?}, validation: {:?})\n{}",
+            outcome,
+            fixed_grid.validate(),
+            fixed_grid.to_string()
+        );
+        fixed_grid.to_png("preplaced.png");
+    }
+    {
+        type Generator = fn(&Grid, &mut rand::rngs::StdRng);
+        let generators: [(&str, Generator); 7] = [
+            ("Aldous-Broder", aldous_broder::AldousBroder::on_with_rng),
+            ("Wilson's", wilsons::Wilsons::on_with_rng),
+            ("Houston", |grid, rng| {
+                houston::Houston::on_with_rng(grid, 0.5, rng);
+            }),
+            ("Hunt and Kill", hunt_and_kill::HuntAndKill::on_with_rng),
+            (
+                "Recursive Backtracker",
+                recursive_backtracker::RecursiveBacktracker::on_with_rng,
+            ),
+            ("Binary Tree", binary_tree::BinaryTree::on_with_rng),
+            ("Sidewinder", side_winder::SideWinder::on_with_rng),
+        ];
+        println!("Uniformity over the perfect mazes of a 3x3 grid");
+        for (name, generator) in generators.iter() {
+            let report = uniformity::Uniformity::sample(3, 3, 19200, 2020, generator);
+            println!(
+                "{}: {}/{} mazes seen in {} runs ({} imperfect), chi-square {:.1} on {} degrees of freedom (z = {:.1})",
+                name,
+                report.distinct,
+                report.trees,
+                report.runs,
+                report.imperfect,
+                report.chi_square,
+                report.degrees_of_freedom,
+                report.z_score()
+            );
+        }
+    }
+    {
+        use blended::{Generator, Region};
+        let mut blended_grid = Grid::initialize(20, 20);
+        blended_grid.configure_cells();
+        let mut south_east = mask::Mask::initialize(20, 20);
+        for row in 0..20 {
+            for column in 0..10 {
+                south_east.set(row, column, false);
+            }
+        }
+        let zones: Vec<(Region, Generator)> = vec![
+            (
+                Region::Rectangle {
+                    row: 0,
+                    column: 0,
+                    height: 10,
+                    width: 20,
+                },
+                Box::new(binary_tree::BinaryTree::on),
+            ),
+            (
+                Region::Cells(Box::new(|_, column| column < 10)),
+                Box::new(recursive_backtracker::RecursiveBacktracker::on),
+            ),
+            (
+                Region::Mask(south_east),
+                Box::new(side_winder::SideWinder::on),
+            ),
+        ];
+        let stitches = blended::Blended::on(&mut blended_grid, &zones, 3);
+        println!(
+            "Here's a binary tree north, backtracker south-west and sidewinder south-east ({:?} stitches, validation: {:?})\n{}",
+            stitches,
+            blended_grid.validate(),
+            blended_grid.to_string()
+        );
+        blended_grid.to_png("blended.png");
+    }
+    {
+        let mut rng = rand::thread_rng();
+        let field = match cost_field::CostField::from_png("costs.png", 30, 30) {
+            Ok(field) => field,
+            Err(_) => cost_field::CostField::value_noise(30, 30, 6.0, &mut rng),
+        };
+
+        let mut prims_grid = Grid::initialize(30, 30);
+        prims_grid.configure_cells();
+        weighted_prims::WeightedPrims::on(&prims_grid, &field);
+        prims_grid.background = Some(field.clone());
+        println!(
+            "Here's a maze by weighted Prim's over a cost field (mean corridor {:.2})\n{}",
+            prims_grid.mean_corridor_length(),
+            prims_grid.to_string()
+        );
+        prims_grid.to_png("weighted_prims.png");
+
+        let mut kruskals_grid = Grid::initialize(30, 30);
+        kruskals_grid.configure_cells();
+        weighted_kruskals::WeightedKruskals::on(&kruskals_grid, &field);
+        println!(
+            "Weighted Kruskal's over the same field: validation {:?}, mean corridor {:.2}",
+            kruskals_grid.validate(),
+            kruskals_grid.mean_corridor_length()
+        );
+        kruskals_grid.background = Some(field);
+        kruskals_grid.to_png("weighted_kruskals.png");
+    }
+    {
+        // The solution spells an S from the top right corner to the bottom left one
+        let mut letter: Vec<Coord> = (2..13).rev().map(|column| Coord::from(1, column)).collect();
+        letter.extend((2..8).map(|row| Coord::from(row, 2)));
+        letter.extend((3..13).map(|column| Coord::from(7, column)));
+        letter.extend((8..14).map(|row| Coord::from(row, 12)));
+        letter.extend((2..12).rev().map(|column| Coord::from(13, column)));
+        letter.insert(0, Coord::from(0, 12));
+        letter.push(Coord::from(14, 2));
+
+        let mut solved_grid = Grid::initialize(15, 15);
+        solved_grid.configure_cells();
+        let start = letter[0].clone();
+        let goal = letter[letter.len() - 1].clone();
+        match solution_path::SolutionPath::on(
+            &mut solved_grid,
+            &letter,
+            recursive_backtracker::RecursiveBacktracker::on,
+        ) {
+            Ok(length) => {
+                solved_grid.distances = solved_grid.find_distances(start);
+                solved_grid.distances = solved_grid.path_to(goal);
+                println!(
+                    "Here's a maze whose {} step solution spells an S\n{}",
+                    length,
+                    solved_grid.to_string()
+                );
+                solved_grid.to_png("solution_path.png");
+            }
+            Err(error) => println!("Couldn't build a maze around the S: {:?}", error),
+        }
+    }
+    {
+        let fractal = fractal::Fractal::on(4, 4, 3, 2, wilsons::Wilsons::on);
+        println!(
+            "Here's a 4x4 maze refined twice into 3x3 blocks (validation: {:?})\n{}",
+            fractal.grid.validate(),
+            fractal.grid.to_string()
+        );
+        fractal.to_png("fractal.png");
+
+        let huge = fractal::Fractal::on(16, 16, 4, 3, hunt_and_kill::HuntAndKill::on);
+        println!(
+            "A {}x{} maze of mazes has {} deadends",
+            huge.grid.rows,
+            huge.grid.columns,
+            huge.grid.deadends()
+        );
+    }
+    {
+        use topology::Topology;
+        type PolarGenerator = fn(&polar_grid::PolarGrid);
+        let polar_generators: [(&str, PolarGenerator); 4] = [
+            ("aldous_broder", aldous_broder::AldousBroder::on),
+            ("wilsons", wilsons::Wilsons::on),
+            ("hunt_and_kill", hunt_and_kill::HuntAndKill::on),
+            (
+                "recursive_backtracker",
+                recursive_backtracker::RecursiveBacktracker::on,
+            ),
+        ];
+        for (name, generator) in polar_generators.iter() {
+            let mut polar = polar_grid::PolarGrid::initialize(12);
+            generator(&polar);
+            polar.distances = polar.find_distances(Coord::from(0, 0));
+            println!(
+                "Round maze of {} cells by {}: {} deadends, farthest cell {:?}",
+                polar.size(),
+                name,
+                polar.deadends(),
+                polar.distances.max()
+            );
+            polar.to_png(&format!("polar_{}.png", name));
+        }
+    }
+    {
+        use topology::Topology;
+        let mut hex = hex_grid::HexGrid::initialize(8, 12);
+        recursive_backtracker::RecursiveBacktracker::on(&hex);
+        hex.distances = hex.find_distances(Coord::from(0, 0));
+        println!(
+            "A hex maze by the recursive backtracker with {} deadends\n{}",
+            hex.deadends(),
+            hex
+        );
+        hex.to_png("hex.png");
+        let board = hex_grid::HexGrid::initialize(20, 30);
+        wilsons::Wilsons::on(&board);
+        board.to_png("hex_board.png");
+    }
+    {
+        use topology::Topology;
+        let mut triangles = triangle_grid::TriangleGrid::initialize(12, 23);
+        hunt_and_kill::HuntAndKill::on(&triangles);
+        triangles.distances = triangles.find_distances(Coord::from(0, 0));
+        println!(
+            "A triangle maze by hunt and kill has {} deadends and its farthest cell is {:?}",
+            triangles.deadends(),
+            triangles.distances.max()
+        );
+        triangles.to_png("triangle.png");
+    }
+    {
+        use topology::Topology;
+        let mut upsilon = upsilon_grid::UpsilonGrid::initialize(15, 15);
+        aldous_broder::AldousBroder::on(&upsilon);
+        upsilon.distances = upsilon.find_distances(Coord::from(0, 0));
+        println!(
+            "An upsilon maze by Aldous-Broder is {} moves from corner to corner",
+            upsilon.distances.get_cell_distance(&Coord::from(14, 14))
+        );
+        upsilon.to_png("upsilon.png");
+    }
+    {
+        use topology::Topology;
+        let mut woven = weave_grid::WeaveGrid::initialize(20, 20);
+        recursive_backtracker::RecursiveBacktracker::on(&woven);
+        woven.distances = woven.find_distances(Coord::from(0, 0));
+        println!(
+            "A woven maze by the recursive backtracker has {} crossings",
+            woven.size() - woven.rows * woven.columns
+        );
+        woven.to_png("weave_recursive_backtracker.png");
+
+        let mut woven = weave_grid::WeaveGrid::initialize(20, 20);
+        let crossings = weave_kruskals::WeaveKruskals::on(&woven, 100);
+        woven.distances = woven.find_distances(Coord::from(0, 0));
+        println!(
+            "A woven maze by Kruskal's has {} crossings and {} deadends",
+            crossings,
+            woven.deadends()
+        );
+        woven.to_png("weave_kruskals.png");
+    }
     println!(
         "Deadends of maze algorithms on a ({}x{}) maze",
         grid.rows, grid.columns
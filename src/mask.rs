@@ -0,0 +1,219 @@
+use std::fs::File;
+
+use cairo::{Format, ImageSurface, IoError};
+use rand::Rng;
+
+use crate::cell::Coord;
+
+#[derive(Debug)]
+pub enum MaskError {
+    Image(IoError),
+    NoCellsOn,
+    Disconnected,
+}
+
+impl From<IoError> for MaskError {
+    fn from(error: IoError) -> MaskError {
+        MaskError::Image(error)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Mask {
+    pub rows: usize,
+    pub columns: usize,
+    bits: Vec<Vec<bool>>,
+}
+
+impl Mask {
+    pub fn initialize(rows: usize, columns: usize) -> Mask {
+        Mask {
+            rows,
+            columns,
+            bits: vec![vec![true; columns]; rows],
+        }
+    }
+
+    // Every character of the template is a cell, and an `X` turns that cell off.
+    // Rows shorter than the widest one are padded with cells that are off.
+    pub fn from_txt(template: &str) -> Result<Mask, MaskError> {
+        let lines: Vec<&str> = template
+            .lines()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let rows = lines.len();
+        let columns = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut mask = Mask::initialize(rows, columns);
+        for (row, line) in lines.iter().enumerate() {
+            let mut chars = line.chars();
+            for column in 0..columns {
+                let on = match chars.next() {
+                    Some('X') | Some('x') | None => false,
+                    Some(_) => true,
+                };
+                mask.set(row, column, on);
+            }
+        }
+        mask.validate()?;
+        Ok(mask)
+    }
+
+    // Each pixel is a cell: dark pixels are off, light or transparent pixels are on
+    pub fn from_png(filename: &str) -> Result<Mask, MaskError> {
+        let mut file = File::open(filename).map_err(IoError::Io)?;
+        let mut surface = ImageSurface::create_from_png(&mut file)?;
+        let rows = surface.get_height() as usize;
+        let columns = surface.get_width() as usize;
+        let stride = surface.get_stride() as usize;
+        let has_alpha = matches!(surface.get_format(), Format::ARgb32);
+        let data = surface
+            .get_data()
+            .expect("Couldn't read the pixels of the mask image");
+        let mut mask = Mask::initialize(rows, columns);
+        for row in 0..rows {
+            for column in 0..columns {
+                // Pixels are native-endian 32 bit words, so alpha is the last byte on little-endian machines
+                let offset = row * stride + column * 4;
+                let pixel = &data[offset..offset + 4];
+                let brightness = (pixel[0] as usize + pixel[1] as usize + pixel[2] as usize) / 3;
+                let transparent = has_alpha && pixel[3] < 128;
+                mask.set(row, column, transparent || brightness >= 128);
+            }
+        }
+        mask.validate()?;
+        Ok(mask)
+    }
+
+    // A maze needs somewhere to start, and random walks never finish on cells they can't reach,
+    // so the cells that are on must form a single region
+    pub fn validate(&self) -> Result<(), MaskError> {
+        let start = self.first_location().ok_or(MaskError::NoCellsOn)?;
+        let mut reached = vec![vec![false; self.columns]; self.rows];
+        reached[start.row()][start.column()] = true;
+        let mut count = 1;
+        let mut frontier = vec![start];
+        while let Some(coord) = frontier.pop() {
+            let (row, column) = (coord.row(), coord.column());
+            let around = [
+                (row.wrapping_sub(1), column),
+                (row + 1, column),
+                (row, column.wrapping_sub(1)),
+                (row, column + 1),
+            ];
+            for (row, column) in around.iter().cloned() {
+                if self.is_on(row, column) && !reached[row][column] {
+                    reached[row][column] = true;
+                    count += 1;
+                    frontier.push(Coord::from(row, column));
+                }
+            }
+        }
+        if count != self.count() {
+            return Err(MaskError::Disconnected);
+        }
+        Ok(())
+    }
+
+    pub fn is_on(&self, row: usize, column: usize) -> bool {
+        self.bits
+            .get(row)
+            .and_then(|bits| bits.get(column))
+            .cloned()
+            .unwrap_or(false)
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, on: bool) {
+        self.bits[row][column] = on;
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|row| row.iter().filter(|bit| **bit).count())
+            .sum()
+    }
+
+    pub fn first_location(&self) -> Option<Coord> {
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                if self.bits[row][column] {
+                    return Some(Coord::from(row, column));
+                }
+            }
+        }
+        None
+    }
+
+    pub fn random_location<R: Rng>(&self, rng: &mut R) -> Option<Coord> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let nth = rng.gen_range(0, count);
+        (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| Coord::from(row, column)))
+            .filter(|coord| self.bits[coord.row()][coord.column()])
+            .nth(nth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_with_every_cell_on() {
+        let mask = Mask::initialize(3, 4);
+        assert_eq!(12, mask.count());
+        assert!(mask.is_on(2, 3));
+        assert!(!mask.is_on(3, 0));
+    }
+
+    #[test]
+    fn turns_cells_off() {
+        let mut mask = Mask::initialize(2, 2);
+        mask.set(0, 0, false);
+        mask.set(1, 1, false);
+        assert_eq!(2, mask.count());
+        assert_eq!(Some(Coord::from(0, 1)), mask.first_location());
+        let location = mask.random_location(&mut rand::thread_rng()).unwrap();
+        assert!(mask.is_on(location.row(), location.column()));
+        mask.set(0, 1, false);
+        mask.set(1, 0, false);
+        assert_eq!(None, mask.random_location(&mut rand::thread_rng()));
+    }
+
+    #[test]
+    fn reads_a_text_template() {
+        let mask = Mask::from_txt("X..\n...\n.X\n").unwrap();
+        assert_eq!((3, 3), (mask.rows, mask.columns));
+        assert_eq!(6, mask.count());
+        assert!(!mask.is_on(0, 0));
+        assert!(!mask.is_on(2, 1));
+        assert!(!mask.is_on(2, 2));
+        assert!(mask.is_on(1, 1));
+    }
+
+    #[test]
+    fn rejects_a_template_with_every_cell_off() {
+        assert!(matches!(
+            Mask::from_txt("XX\nX\n"),
+            Err(MaskError::NoCellsOn)
+        ));
+        assert!(matches!(Mask::from_txt(""), Err(MaskError::NoCellsOn)));
+    }
+    #[test]
+    fn rejects_a_template_with_separate_islands() {
+        let template = "..X..\n..X..\nXXX..\n";
+        assert!(matches!(
+            Mask::from_txt(template),
+            Err(MaskError::Disconnected)
+        ));
+        assert!(Mask::from_txt("..X..\n.....\n").is_ok());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::cell::Coord;
+use crate::grid::Grid;
+
+pub struct OriginShift {
+    pub root: Coord,
+    parents: Vec<Vec<Option<Coord>>>,
+}
+
+#[derive(Debug)]
+pub struct Shift {
+    pub opened: (Coord, Coord),
+    pub closed: (Coord, Coord),
+}
+
+impl OriginShift {
+    pub fn on(grid: &Grid, steps: usize) -> OriginShift {
+        // Any spanning tree will do as a start; a breadth-first one also covers masked grids
+        let root = (0..grid.rows * grid.columns)
+            .rev()
+            .map(|index| Coord::from(index / grid.columns, index % grid.columns))
+            .find(|coord| grid.is_active(coord.row(), coord.column()))
+            .expect("Origin shift needs at least one active cell");
+        let mut parents = vec![vec![None; grid.columns]; grid.rows];
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(root.clone());
+        queue.push_back(root.clone());
+        while let Some(coord) = queue.pop_front() {
+            let neighbours = grid.grid[coord.row()][coord.column()].borrow().neighbours();
+            for weak_ref in neighbours {
+                let neighbour = weak_ref.upgrade().unwrap();
+                let neighbour = Coord::from(neighbour.borrow().row, neighbour.borrow().column);
+                if seen.insert(neighbour.clone()) {
+                    grid.link(&coord, &neighbour);
+                    parents[neighbour.row()][neighbour.column()] = Some(coord.clone());
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        let mut origin_shift = OriginShift { root, parents };
+        for _ in 0..steps {
+            origin_shift.step(grid);
+        }
+        origin_shift
+    }
+
+    pub fn rooted(grid: &Grid, root: Coord) -> Option<OriginShift> {
+        let mut parents = vec![vec![None; grid.columns]; grid.rows];
+        let mut seen = HashSet::new();
+        let mut link_count = 0;
+        let mut frontier = vec![root.clone()];
+        seen.insert(root.clone());
+        while let Some(coord) = frontier.pop() {
+            let cell = grid.grid[coord.row()][coord.column()].clone();
+            for link in &cell.borrow().links {
+                link_count += 1;
+                if seen.insert(link.clone()) {
+                    parents[link.row()][link.column()] = Some(coord.clone());
+                    frontier.push(link.clone());
+                }
+            }
+        }
+        if seen.len() != grid.size() || link_count / 2 != grid.size() - 1 {
+            return None;
+        }
+        Some(OriginShift { root, parents })
+    }
+
+    pub fn step(&mut self, grid: &Grid) -> Option<Shift> {
+        let root_ref = grid
+            .get_cell_ref(self.root.row(), self.root.column())
+            .unwrap();
+        let neighbours = root_ref.upgrade().unwrap().borrow().neighbours();
+        if neighbours.is_empty() {
+            return None;
+        }
+        let neighbour = neighbours[rand::random::<usize>() % neighbours.len()]
+            .upgrade()
+            .unwrap();
+        let new_root = Coord::from(neighbour.borrow().row, neighbour.borrow().column);
+        let old_root = std::mem::replace(&mut self.root, new_root.clone());
+        let old_parent = self.parents[new_root.row()][new_root.column()]
+            .take()
+            .unwrap();
+        self.parents[old_root.row()][old_root.column()] = Some(new_root.clone());
+        if old_parent == old_root {
+            return None;
+        }
+        grid.unlink(&new_root, &old_parent);
+        grid.link(&old_root, &new_root);
+        Some(Shift {
+            opened: (old_root, new_root.clone()),
+            closed: (new_root, old_parent),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+
+    #[test]
+    fn keeps_a_spanning_tree_while_shifting() {
+        let mut grid = Grid::initialize(8, 11);
+        grid.configure_cells();
+        let mut origin_shift = OriginShift::on(&grid, 500);
+        assert!(grid.is_perfect());
+        for _ in 0..200 {
+            origin_shift.step(&grid);
+            assert!(grid.is_perfect());
+        }
+
+        let root = origin_shift.root.clone();
+        let mut rerooted = OriginShift::rooted(&grid, root).unwrap();
+        rerooted.step(&grid);
+        assert!(grid.is_perfect());
+    }
+
+    #[test]
+    fn only_roots_spanning_trees() {
+        let mut grid = Grid::initialize(2, 2);
+        grid.configure_cells();
+        grid.link(&Coord::from(0, 0), &Coord::from(0, 1));
+        assert!(OriginShift::rooted(&grid, Coord::from(0, 0)).is_none());
+        grid.link(&Coord::from(0, 1), &Coord::from(1, 1));
+        grid.link(&Coord::from(1, 1), &Coord::from(1, 0));
+        assert!(OriginShift::rooted(&grid, Coord::from(0, 0)).is_some());
+        grid.link(&Coord::from(1, 0), &Coord::from(0, 0));
+        assert!(OriginShift::rooted(&grid, Coord::from(0, 0)).is_none());
+    }
+}
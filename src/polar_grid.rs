@@ -0,0 +1,123 @@
+use std::f64::consts::PI;
+
+use crate::cell::Coord;
+use crate::distances::Distances;
+use crate::topology::{Passages, Shape};
+
+// A round grid of `rings` rings around a single centre cell. A cell is named by its ring
+// (as the row) and its position clockwise from east within the ring (as the column).
+#[derive(Debug)]
+pub struct PolarGrid {
+    pub rings: usize,
+    pub ring_sizes: Vec<usize>,
+    passages: Passages,
+    pub distances: Distances,
+}
+
+impl PolarGrid {
+    // Each ring splits its cells whenever they would grow more than about half again as
+    // wide as they are tall, so cells keep a similar size all the way out
+    pub fn initialize(rings: usize) -> PolarGrid {
+        let mut ring_sizes = vec![1];
+        for ring in 1..rings {
+            let previous = ring_sizes[ring - 1];
+            let circumference = 2.0 * PI * ring as f64;
+            let ratio = (circumference / previous as f64).round().max(1.0) as usize;
+            ring_sizes.push(previous * ratio);
+        }
+        PolarGrid {
+            rings,
+            passages: Passages::rows(&ring_sizes),
+            ring_sizes,
+            distances: Distances::initialize(Coord::from(0, 0)),
+        }
+    }
+
+    pub fn inward(&self, coord: &Coord) -> Option<Coord> {
+        if coord.row() == 0 {
+            return None;
+        }
+        let ratio = self.ring_sizes[coord.row()] / self.ring_sizes[coord.row() - 1];
+        Some(Coord::from(coord.row() - 1, coord.column() / ratio))
+    }
+
+    pub fn outward(&self, coord: &Coord) -> Vec<Coord> {
+        if coord.row() + 1 >= self.rings {
+            return Vec::new();
+        }
+        let ratio = self.ring_sizes[coord.row() + 1] / self.ring_sizes[coord.row()];
+        (0..ratio)
+            .map(|child| Coord::from(coord.row() + 1, coord.column() * ratio + child))
+            .collect()
+    }
+
+    pub fn clockwise(&self, coord: &Coord) -> Option<Coord> {
+        let size = self.ring_sizes[coord.row()];
+        if size < 2 {
+            return None;
+        }
+        Some(Coord::from(coord.row(), (coord.column() + 1) % size))
+    }
+
+    pub fn counter_clockwise(&self, coord: &Coord) -> Option<Coord> {
+        let size = self.ring_sizes[coord.row()];
+        if size < 3 {
+            return None;
+        }
+        Some(Coord::from(coord.row(), (coord.column() + size - 1) % size))
+    }
+
+    // The angles of a cell's counter-clockwise and clockwise edges, in radians from east
+    pub fn angles(&self, coord: &Coord) -> (f64, f64) {
+        let theta = 2.0 * PI / self.ring_sizes[coord.row()] as f64;
+        (
+            coord.column() as f64 * theta,
+            (coord.column() + 1) as f64 * theta,
+        )
+    }
+}
+
+impl Shape for PolarGrid {
+    fn passages(&self) -> &Passages {
+        &self.passages
+    }
+
+    fn adjacent(&self, coord: &Coord) -> Vec<Coord> {
+        let mut neighbours = Vec::new();
+        neighbours.extend(self.inward(coord));
+        neighbours.extend(self.clockwise(coord));
+        neighbours.extend(self.counter_clockwise(coord));
+        neighbours.extend(self.outward(coord));
+        neighbours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+    use crate::topology::Topology;
+
+    #[test]
+    fn subdivides_rings_outward() {
+        let grid = PolarGrid::initialize(6);
+        assert_eq!(vec![1, 6, 12, 24, 24, 24], grid.ring_sizes);
+        assert_eq!(
+            vec![Coord::from(2, 2), Coord::from(2, 3)],
+            grid.outward(&Coord::from(1, 1))
+        );
+        assert_eq!(Some(Coord::from(1, 1)), grid.inward(&Coord::from(2, 3)));
+        assert_eq!(Some(Coord::from(3, 0)), grid.clockwise(&Coord::from(3, 23)));
+        assert_eq!(6, grid.neighbours(&Coord::from(0, 0)).len());
+        for index in 0..grid.capacity() {
+            assert_eq!(index, grid.index(&grid.coord(index)));
+        }
+    }
+
+    #[test]
+    fn carves_a_perfect_round_maze() {
+        let grid = PolarGrid::initialize(5);
+        RecursiveBacktracker::on(&grid);
+        assert!(grid.is_perfect());
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+
+use crate::cell::Coord;
+use crate::disjoint_set::DisjointSet;
+use crate::grid::Grid;
+
+#[derive(Debug, Default, Clone)]
+pub struct Fixtures {
+    pub passages: Vec<(Coord, Coord)>,
+    pub walls: Vec<(Coord, Coord)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PreplacedError {
+    NotNeighbours(Coord, Coord),
+    PassageThroughWall(Coord, Coord),
+    WalledOff { reached: usize, size: usize },
+}
+
+// `loops` holds the protected passages that close a loop among the earlier ones, such as the
+// last side of a ring. They are kept, so the maze has exactly one loop for each of them.
+#[derive(Debug, PartialEq)]
+pub struct Placement {
+    pub dropped: usize,
+    pub loops: Vec<(Coord, Coord)>,
+}
+
+impl Fixtures {
+    // Protects a passage between every pair of consecutive cells on the path
+    pub fn carve_path(&mut self, path: &[Coord]) {
+        for pair in path.windows(2) {
+            self.passages.push((pair[0].clone(), pair[1].clone()));
+        }
+    }
+}
+
+pub struct Preplaced {}
+
+impl Preplaced {
+    // The generator runs with the forbidden walls already standing. Its passages are then
+    // merged with the protected ones, dropping any that would close a loop, and the regions
+    // left over are joined up. Returns the number of generated passages that were dropped
+    // along with any loops the protected passages force on the maze.
+    pub fn on<F: Fn(&Grid)>(
+        grid: &mut Grid,
+        fixtures: &Fixtures,
+        generator: F,
+    ) -> Result<Placement, PreplacedError> {
+        let index_of = |coord: &Coord| coord.row() * grid.columns + coord.column();
+        let mut regions = DisjointSet::initialize(grid.rows * grid.columns);
+        let mut loops = Vec::new();
+        for (from, to) in fixtures.walls.iter() {
+            check_neighbours(grid, from, to)?;
+        }
+        for (from, to) in fixtures.passages.iter() {
+            check_neighbours(grid, from, to)?;
+            if fixtures
+                .walls
+                .iter()
+                .any(|(a, b)| (a == from && b == to) || (a == to && b == from))
+            {
+                return Err(PreplacedError::PassageThroughWall(from.clone(), to.clone()));
+            }
+            if !regions.union(index_of(from), index_of(to)) {
+                loops.push((from.clone(), to.clone()));
+            }
+        }
+
+        for (from, to) in fixtures.walls.iter() {
+            detach(grid, from, to);
+        }
+        let reached = reachable(grid);
+        if reached != grid.size() {
+            grid.configure_cells();
+            return Err(PreplacedError::WalledOff {
+                reached,
+                size: grid.size(),
+            });
+        }
+
+        generator(grid);
+        let mut generated = Vec::new();
+        for cell in grid.grid.iter().flatten() {
+            let mut cell = cell.borrow_mut();
+            let coord = Coord::from(cell.row, cell.column);
+            for link in cell.links.drain() {
+                if index_of(&coord) < index_of(&link) {
+                    generated.push((coord.clone(), link));
+                }
+            }
+        }
+
+        for (from, to) in fixtures.passages.iter() {
+            grid.link(from, to);
+        }
+        let mut dropped = 0;
+        for (from, to) in generated {
+            if regions.union(index_of(&from), index_of(&to)) {
+                grid.link(&from, &to);
+            } else {
+                dropped += 1;
+            }
+        }
+        grid.join_regions(&mut rand::thread_rng());
+        grid.configure_cells();
+        Ok(Placement { dropped, loops })
+    }
+}
+
+fn check_neighbours(grid: &Grid, from: &Coord, to: &Coord) -> Result<(), PreplacedError> {
+    let row_gap = (from.row() as isize - to.row() as isize).abs();
+    let column_gap = (from.column() as isize - to.column() as isize).abs();
+    if row_gap + column_gap == 1
+        && grid.is_active(from.row(), from.column())
+        && grid.is_active(to.row(), to.column())
+    {
+        Ok(())
+    } else {
+        Err(PreplacedError::NotNeighbours(from.clone(), to.clone()))
+    }
+}
+
+fn detach(grid: &Grid, from: &Coord, to: &Coord) {
+    let (first, second) = if (from.row(), from.column()) < (to.row(), to.column()) {
+        (from, to)
+    } else {
+        (to, from)
+    };
+    let mut first = grid.grid[first.row()][first.column()].borrow_mut();
+    let mut second = grid.grid[second.row()][second.column()].borrow_mut();
+    if first.row == second.row {
+        first.east = None;
+        second.west = None;
+    } else {
+        first.south = None;
+        second.north = None;
+    }
+}
+
+fn reachable(grid: &Grid) -> usize {
+    let start = match grid.mask.first_location() {
+        Some(start) => start,
+        None => return 0,
+    };
+    let mut seen = vec![false; grid.rows * grid.columns];
+    let mut queue = VecDeque::new();
+    seen[start.row() * grid.columns + start.column()] = true;
+    queue.push_back(grid.grid[start.row()][start.column()].clone());
+    let mut reached = 0;
+    while let Some(cell) = queue.pop_front() {
+        reached += 1;
+        for weak_ref in cell.borrow().neighbours() {
+            let neighbour = weak_ref.upgrade().unwrap();
+            let index = neighbour.borrow().row * grid.columns + neighbour.borrow().column;
+            if !seen[index] {
+                seen[index] = true;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+    use crate::topology::Topology;
+
+    fn grid() -> Grid {
+        let mut grid = Grid::initialize(6, 6);
+        grid.configure_cells();
+        grid
+    }
+
+    #[test]
+    fn keeps_fixed_passages_and_walls_in_a_perfect_maze() {
+        let mut fixtures = Fixtures::default();
+        let hall: Vec<Coord> = (0..6).map(|row| Coord::from(row, 2)).collect();
+        let fence: Vec<Coord> = (0..6).map(|row| Coord::from(row, 3)).collect();
+        fixtures.carve_path(&hall);
+        for (left, right) in hall.iter().zip(fence.iter()).skip(1) {
+            fixtures.walls.push((left.clone(), right.clone()));
+        }
+
+        let mut grid = grid();
+        assert!(Preplaced::on(&mut grid, &fixtures, RecursiveBacktracker::on).is_ok());
+        assert_eq!(Ok(()), grid.validate());
+        for (from, to) in fixtures.passages.iter() {
+            assert!(!grid.has_wall(from, to));
+        }
+        for (from, to) in fixtures.walls.iter() {
+            assert!(grid.has_wall(from, to));
+        }
+        assert!(grid.is_perfect());
+    }
+
+    #[test]
+    fn keeps_a_ring_and_adds_no_other_loops() {
+        let mut ring: Vec<Coord> = (1..5).map(|column| Coord::from(1, column)).collect();
+        ring.extend((2..5).map(|row| Coord::from(row, 4)));
+        ring.extend((1..4).rev().map(|column| Coord::from(4, column)));
+        ring.extend((1..4).rev().map(|row| Coord::from(row, 1)));
+        let mut fixtures = Fixtures::default();
+        fixtures.carve_path(&ring);
+
+        let mut grid = grid();
+        let placement = Preplaced::on(&mut grid, &fixtures, RecursiveBacktracker::on).unwrap();
+        assert_eq!(
+            vec![(Coord::from(2, 1), Coord::from(1, 1))],
+            placement.loops
+        );
+        assert_eq!(Ok(()), grid.validate());
+        for (from, to) in fixtures.passages.iter() {
+            assert!(!grid.has_wall(from, to));
+        }
+        let passages: usize = grid
+            .cells()
+            .iter()
+            .map(|coord| grid.links(coord).len())
+            .sum();
+        assert_eq!(grid.size(), passages / 2);
+    }
+
+    #[test]
+    fn reports_impossible_fixtures() {
+        let mut fixtures = Fixtures::default();
+        fixtures.walls.push((Coord::from(0, 0), Coord::from(0, 1)));
+        fixtures.walls.push((Coord::from(0, 0), Coord::from(1, 0)));
+        let outcome = Preplaced::on(&mut grid(), &fixtures, RecursiveBacktracker::on);
+        assert_eq!(
+            Err(PreplacedError::WalledOff {
+                reached: 1,
+                size: 36
+            }),
+            outcome
+        );
+    }
+}
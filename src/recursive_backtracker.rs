@@ -1,34 +1,94 @@
 use std::rc::Rc;
 
-use rand;
+use rand::Rng;
 
-use crate::cell::{GridCell, GridCellRef};
+use crate::cell::{Coord, GridCell, GridCellRef, Side};
 use crate::grid::Grid;
+use crate::topology::Topology;
+
+const SIDES: [Side; 4] = [Side::North, Side::South, Side::East, Side::West];
 
 pub struct RecursiveBacktracker {}
 
 impl RecursiveBacktracker {
-    pub fn on(grid: &Grid) {
-        let start = grid.get_random_cell();
-        let mut stack = Vec::new();
-        stack.push(start);
-        while !stack.is_empty() {
-            let current = stack.last().unwrap().upgrade().unwrap();
-            let unvisited_neighbours = current
-                .borrow()
-                .neighbours()
+    pub fn on<T: Topology>(grid: &T) {
+        RecursiveBacktracker::on_with_rng(grid, &mut rand::thread_rng());
+    }
+
+    pub fn on_with_rng<T: Topology, R: Rng>(grid: &T, rng: &mut R) {
+        let mut stack = vec![grid.random_cell(rng)];
+        while let Some(current) = stack.last() {
+            let unvisited_neighbours = grid
+                .neighbours(current)
                 .into_iter()
-                .map(|weak_ref| weak_ref.upgrade().unwrap())
-                .filter(|cell_ref| cell_ref.borrow().links.is_empty())
-                .collect::<Vec<GridCellRef>>();
+                .filter(|neighbour| grid.links(neighbour).is_empty())
+                .collect::<Vec<Coord>>();
             if unvisited_neighbours.is_empty() {
                 stack.pop();
             } else {
-                let rand_num = rand::random::<usize>() % unvisited_neighbours.len();
-                let neighbour = unvisited_neighbours.get(rand_num).unwrap();
-                GridCell::link(&mut current.borrow_mut(), &mut neighbour.borrow_mut());
-                stack.push(Rc::downgrade(neighbour));
+                let rand_num = rng.gen_range(0, unvisited_neighbours.len());
+                let neighbour = unvisited_neighbours[rand_num].clone();
+                grid.link(current, &neighbour);
+                stack.push(neighbour);
+            }
+        }
+    }
+
+    // `straightness` is the chance of carving on in the direction the walk entered a cell
+    // whenever that cell is still unvisited: 1.0 gives long corridors, 0.0 tight zigzags.
+    pub fn on_with_straightness<R: Rng>(grid: &Grid, straightness: f64, rng: &mut R) {
+        let start = grid.get_random_cell_with(rng);
+        let mut stack = vec![(start, None)];
+        while let Some((current, heading)) = stack.last().cloned() {
+            let current = current.upgrade().unwrap();
+            let unvisited_neighbours = SIDES
+                .iter()
+                .filter_map(|side| {
+                    let neighbour = current.borrow().neighbour(*side)?.upgrade().unwrap();
+                    if neighbour.borrow().links.is_empty() {
+                        Some((*side, neighbour))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<(Side, GridCellRef)>>();
+            if unvisited_neighbours.is_empty() {
+                stack.pop();
+                continue;
             }
+            let (straight, turns): (Vec<_>, Vec<_>) = unvisited_neighbours
+                .into_iter()
+                .partition(|(side, _)| Some(*side) == heading);
+            let choices =
+                if turns.is_empty() || (!straight.is_empty() && rng.gen::<f64>() < straightness) {
+                    straight
+                } else {
+                    turns
+                };
+            let (side, neighbour) = &choices[rng.gen_range(0, choices.len())];
+            GridCell::link(&mut current.borrow_mut(), &mut neighbour.borrow_mut());
+            stack.push((Rc::downgrade(neighbour), Some(*side)));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn straightness_lengthens_corridors() {
+        let corridor = |straightness: f64| {
+            let mut grid = Grid::initialize(20, 20);
+            grid.configure_cells();
+            let mut rng = StdRng::seed_from_u64(9);
+            RecursiveBacktracker::on_with_straightness(&grid, straightness, &mut rng);
+            assert!(grid.is_perfect());
+            grid.mean_corridor_length()
+        };
+        let (winding, straight) = (corridor(0.0), corridor(1.0));
+        assert!(straight > 2.0 * winding, "{} against {}", straight, winding);
+    }
+}
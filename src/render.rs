@@ -1,8 +1,15 @@
 use cairo::{Context, Format, ImageSurface};
 use std::fs::File;
 
-use crate::cell::{Coord, GridCellRef};
+use crate::cell::{Coord, GridCellRef, Side};
+use crate::distances::Distances;
 use crate::grid::Grid;
+use crate::hex_grid::HexGrid;
+use crate::polar_grid::PolarGrid;
+use crate::topology::Topology;
+use crate::triangle_grid::TriangleGrid;
+use crate::upsilon_grid::UpsilonGrid;
+use crate::weave_grid::WeaveGrid;
 
 enum Direction {
     Horizontal,
@@ -17,6 +24,7 @@ pub struct Dimension {
 #[allow(unused_variables)]
 pub trait Renderable {
     fn to_png(&self, filename: &str) {}
+    fn to_png_path(&self, filename: &str) {}
     fn draw_square_cell(
         &self,
         context: &Context,
@@ -29,68 +37,71 @@ pub trait Renderable {
     }
 }
 
+const PADDING: f64 = 10.0;
+
 impl Renderable for Grid {
     fn to_png(&self, filename: &str) {
-        const PADDING: f64 = 10.0;
-        let image_width = (self.columns * 30) as i32 + (2 * PADDING as i32);
-        let image_height = (self.rows * 30) as i32 + (2 * PADDING as i32);
-        let surface = ImageSurface::create(Format::ARgb32, image_width, image_height)
-            .expect("Could not generate ImageSurface");
-        let context = Context::new(&surface);
-        context.set_source_rgb(1.0, 1.0, 1.0);
-        context.paint();
-
-        context.set_source_rgb(0.0, 0.0, 0.0);
-
-        // Draw northern border
-        context.line_to(PADDING, PADDING);
-        context.line_to(image_width as f64 - PADDING, PADDING);
-        context.stroke();
+        let surface = self.draw_walls();
+        write_png(&surface, filename);
+    }
 
-        for row in 0..self.rows {
-            // Draw western border
-            context.line_to(PADDING as f64, PADDING + (30 * row) as f64);
-            context.line_to(PADDING as f64, PADDING + (30 * (row + 1)) as f64);
-            context.stroke();
+    // Draws the single passage of a unicursal grid as a smooth line through the cell centres
+    fn to_png_path(&self, filename: &str) {
+        let surface = self.draw_walls();
+        let context = Context::new(&surface);
+        let mut current = self
+            .grid
+            .iter()
+            .flatten()
+            .find(|cell| cell.borrow().links.len() == 1)
+            .or_else(|| {
+                self.grid
+                    .iter()
+                    .flatten()
+                    .find(|cell| !cell.borrow().links.is_empty())
+            })
+            .cloned();
+        let mut previous: Option<Coord> = None;
+        let mut points = Vec::new();
+        while let Some(cell) = current {
+            let cell = cell.borrow();
+            let coord = Coord::from(cell.row, cell.column);
+            points.push((
+                PADDING + 15.0 + (30 * cell.column) as f64,
+                PADDING + 15.0 + (30 * cell.row) as f64,
+            ));
+            current = cell
+                .links
+                .iter()
+                .find(|link| Some(*link) != previous.as_ref() && points.len() < self.size())
+                .map(|link| self.grid[link.row()][link.column()].clone());
+            previous = Some(coord);
+        }
+        if points.len() < 2 {
+            write_png(&surface, filename);
+            return;
+        }
 
-            for col in 0..self.columns {
-                let cell = &self.grid[row][col];
-                let mut box_dimension = Dimension {
-                    width: 28.0,
-                    height: 28.0,
-                };
-                if let Some(eastern_neighbour) = &cell.borrow().east {
-                    let eastern_ref = eastern_neighbour.upgrade().unwrap();
-                    let eastern_coords =
-                        Coord::from(eastern_ref.borrow().row, eastern_ref.borrow().column);
-                    if cell.borrow().is_linked(&eastern_coords) {
-                        box_dimension.width = 30.0;
-                    } else {
-                        draw_line(Direction::Vertical, &context, row, col + 1, PADDING);
-                    }
-                } else {
-                    draw_line(Direction::Vertical, &context, row, col + 1, PADDING);
-                }
-                if let Some(southern_neighbour) = &cell.borrow().south {
-                    let southern_ref = southern_neighbour.upgrade().unwrap();
-                    let southern_coords =
-                        Coord::from(southern_ref.borrow().row, southern_ref.borrow().column);
-                    if cell.borrow().is_linked(&southern_coords) {
-                        box_dimension.height = 30.0;
-                    } else {
-                        draw_line(Direction::Horizontal, &context, row + 1, col, PADDING);
-                    }
-                } else {
-                    draw_line(Direction::Horizontal, &context, row + 1, col, PADDING);
-                }
-                self.draw_square_cell(&context, cell, col, row, PADDING, box_dimension);
-            }
+        context.set_source_rgb(0.8, 0.2, 0.2);
+        context.set_line_width(6.0);
+        context.move_to(points[0].0, points[0].1);
+        for window in points.windows(3) {
+            let (corner, next) = (window[1], window[2]);
+            context.curve_to(
+                corner.0,
+                corner.1,
+                corner.0,
+                corner.1,
+                (corner.0 + next.0) / 2.0,
+                (corner.1 + next.1) / 2.0,
+            );
         }
-        let mut file = File::create(filename).expect("Couldn't create an output file");
-        surface
-            .write_to_png(&mut file)
-            .expect("Couldn't write to output file");
+        let last = points[points.len() - 1];
+        context.line_to(last.0, last.1);
+        context.stroke();
+        write_png(&surface, filename);
     }
+
     fn draw_square_cell(
         &self,
         context: &Context,
@@ -112,6 +123,10 @@ impl Renderable for Grid {
             intensity = (max_distance - distance) as f64 / max_distance as f64;
             let bright = 0.5 + (0.4 * intensity);
             context.set_source_rgb(bright, intensity, bright);
+        } else if let Some(field) = &self.background {
+            // Cells off the distance map show the cost field as a grey level
+            let shade = 0.3 + 0.7 * field.cost(cell.borrow().row, cell.borrow().column);
+            context.set_source_rgb(shade, shade, shade);
         } else {
             intensity = 0.0;
             let bright = 0.5 + (0.4 * intensity);
@@ -128,6 +143,413 @@ impl Renderable for Grid {
     }
 }
 
+impl Grid {
+    fn draw_walls(&self) -> ImageSurface {
+        let image_width = (self.columns * 30) as i32 + (2 * PADDING as i32);
+        let image_height = (self.rows * 30) as i32 + (2 * PADDING as i32);
+        let surface = ImageSurface::create(Format::ARgb32, image_width, image_height)
+            .expect("Could not generate ImageSurface");
+        let context = Context::new(&surface);
+        context.set_source_rgb(1.0, 1.0, 1.0);
+        context.paint();
+
+        context.set_source_rgb(0.0, 0.0, 0.0);
+
+        // Draw northern border
+        for col in 0..self.columns {
+            if self.is_active(0, col) {
+                draw_line(Direction::Horizontal, &context, 0, col, PADDING);
+            }
+        }
+
+        for row in 0..self.rows {
+            // Draw western border
+            if self.is_active(row, 0) {
+                draw_line(Direction::Vertical, &context, row, 0, PADDING);
+            }
+
+            for col in 0..self.columns {
+                let cell = &self.grid[row][col];
+                let coords = Coord::from(row, col);
+                let mut box_dimension = Dimension {
+                    width: 28.0,
+                    height: 28.0,
+                };
+                if self.has_wall(&coords, &Coord::from(row, col + 1)) {
+                    draw_line(Direction::Vertical, &context, row, col + 1, PADDING);
+                } else {
+                    box_dimension.width = 30.0;
+                }
+                if self.has_wall(&coords, &Coord::from(row + 1, col)) {
+                    draw_line(Direction::Horizontal, &context, row + 1, col, PADDING);
+                } else {
+                    box_dimension.height = 30.0;
+                }
+                if self.is_active(row, col) {
+                    self.draw_square_cell(&context, cell, col, row, PADDING, box_dimension);
+                }
+            }
+        }
+        surface
+    }
+}
+
+impl Renderable for PolarGrid {
+    fn to_png(&self, filename: &str) {
+        const RING_HEIGHT: f64 = 30.0;
+        let radius = self.rings as f64 * RING_HEIGHT;
+        let size = (2.0 * (radius + PADDING)) as i32;
+        let centre = radius + PADDING;
+        let surface = ImageSurface::create(Format::ARgb32, size, size)
+            .expect("Could not generate ImageSurface");
+        let context = Context::new(&surface);
+        context.set_source_rgb(1.0, 1.0, 1.0);
+        context.paint();
+
+        for coord in self.cells() {
+            if let Some((red, green, blue)) = heat(&self.distances, &coord) {
+                context.set_source_rgb(red, green, blue);
+                let inner = coord.row() as f64 * RING_HEIGHT;
+                let outer = inner + RING_HEIGHT;
+                if coord.row() == 0 {
+                    context.arc(centre, centre, outer, 0.0, 2.0 * std::f64::consts::PI);
+                } else {
+                    let (counter_clockwise, clockwise) = self.angles(&coord);
+                    context.arc(centre, centre, inner, counter_clockwise, clockwise);
+                    context.arc_negative(centre, centre, outer, clockwise, counter_clockwise);
+                }
+                context.close_path();
+                context.fill();
+            }
+        }
+
+        context.set_source_rgb(0.0, 0.0, 0.0);
+        for coord in self.cells().iter().filter(|coord| coord.row() > 0) {
+            let inner = coord.row() as f64 * RING_HEIGHT;
+            let outer = inner + RING_HEIGHT;
+            let (counter_clockwise, clockwise) = self.angles(coord);
+            let inward = self.inward(coord).unwrap();
+            if !self.is_linked(coord, &inward) {
+                context.new_path();
+                context.arc(centre, centre, inner, counter_clockwise, clockwise);
+                context.stroke();
+            }
+            let linked_clockwise = self
+                .clockwise(coord)
+                .is_some_and(|neighbour| self.is_linked(coord, &neighbour));
+            if !linked_clockwise {
+                context.move_to(
+                    centre + inner * clockwise.cos(),
+                    centre + inner * clockwise.sin(),
+                );
+                context.line_to(
+                    centre + outer * clockwise.cos(),
+                    centre + outer * clockwise.sin(),
+                );
+                context.stroke();
+            }
+        }
+        context.new_path();
+        context.arc(centre, centre, radius, 0.0, 2.0 * std::f64::consts::PI);
+        context.stroke();
+        write_png(&surface, filename);
+    }
+}
+
+impl Renderable for HexGrid {
+    fn to_png(&self, filename: &str) {
+        const SIZE: f64 = 20.0;
+        let a_size = SIZE / 2.0;
+        let b_size = SIZE * 3f64.sqrt() / 2.0;
+        let height = 2.0 * b_size;
+        let width = 3.0 * a_size * self.columns as f64 + a_size + 2.0 * PADDING;
+        let image_height = height * self.rows as f64 + b_size + 2.0 * PADDING;
+        let surface = ImageSurface::create(Format::ARgb32, width as i32, image_height as i32)
+            .expect("Could not generate ImageSurface");
+        let context = Context::new(&surface);
+        context.set_source_rgb(1.0, 1.0, 1.0);
+        context.paint();
+
+        for coord in self.cells() {
+            let centre_x = PADDING + SIZE + 3.0 * coord.column() as f64 * a_size;
+            let centre_y = PADDING
+                + b_size
+                + coord.row() as f64 * height
+                + (coord.column() % 2) as f64 * b_size;
+            // The corners, from the far west clockwise round to the south west
+            let corners = [
+                (centre_x - SIZE, centre_y),
+                (centre_x - a_size, centre_y - b_size),
+                (centre_x + a_size, centre_y - b_size),
+                (centre_x + SIZE, centre_y),
+                (centre_x + a_size, centre_y + b_size),
+                (centre_x - a_size, centre_y + b_size),
+            ];
+            if let Some((red, green, blue)) = heat(&self.distances, &coord) {
+                context.set_source_rgb(red, green, blue);
+                context.move_to(corners[0].0, corners[0].1);
+                for corner in corners.iter().skip(1) {
+                    context.line_to(corner.0, corner.1);
+                }
+                context.close_path();
+                context.fill();
+            }
+
+            context.set_source_rgb(0.0, 0.0, 0.0);
+            let sides = [
+                self.northwest(&coord),
+                self.north(&coord),
+                self.northeast(&coord),
+                self.southeast(&coord),
+                self.south(&coord),
+                self.southwest(&coord),
+            ];
+            for (side, neighbour) in sides.iter().enumerate() {
+                let linked = neighbour
+                    .as_ref()
+                    .is_some_and(|neighbour| self.is_linked(&coord, neighbour));
+                if !linked {
+                    let (from, to) = (corners[side], corners[(side + 1) % 6]);
+                    context.move_to(from.0, from.1);
+                    context.line_to(to.0, to.1);
+                    context.stroke();
+                }
+            }
+        }
+        write_png(&surface, filename);
+    }
+}
+
+impl Renderable for TriangleGrid {
+    fn to_png(&self, filename: &str) {
+        const SIZE: f64 = 30.0;
+        let half_width = SIZE / 2.0;
+        let height = SIZE * 3f64.sqrt() / 2.0;
+        let width = half_width * (self.columns + 1) as f64 + 2.0 * PADDING;
+        let image_height = height * self.rows as f64 + 2.0 * PADDING;
+        let surface = ImageSurface::create(Format::ARgb32, width as i32, image_height as i32)
+            .expect("Could not generate ImageSurface");
+        let context = Context::new(&surface);
+        context.set_source_rgb(1.0, 1.0, 1.0);
+        context.paint();
+
+        for coord in self.cells() {
+            let centre_x = PADDING + half_width + coord.column() as f64 * half_width;
+            let top = PADDING + coord.row() as f64 * height;
+            let bottom = top + height;
+            let (apex_y, base_y) = if self.is_upright(&coord) {
+                (top, bottom)
+            } else {
+                (bottom, top)
+            };
+            let west = (centre_x - half_width, base_y);
+            let apex = (centre_x, apex_y);
+            let east = (centre_x + half_width, base_y);
+            if let Some((red, green, blue)) = heat(&self.distances, &coord) {
+                context.set_source_rgb(red, green, blue);
+                context.move_to(west.0, west.1);
+                context.line_to(apex.0, apex.1);
+                context.line_to(east.0, east.1);
+                context.close_path();
+                context.fill();
+            }
+
+            context.set_source_rgb(0.0, 0.0, 0.0);
+            let sides = [
+                (self.west(&coord), west, apex),
+                (self.east(&coord), apex, east),
+                (self.base(&coord), east, west),
+            ];
+            for (neighbour, from, to) in sides.iter() {
+                let linked = neighbour
+                    .as_ref()
+                    .is_some_and(|neighbour| self.is_linked(&coord, neighbour));
+                if !linked {
+                    context.move_to(from.0, from.1);
+                    context.line_to(to.0, to.1);
+                    context.stroke();
+                }
+            }
+        }
+        write_png(&surface, filename);
+    }
+}
+
+impl Renderable for UpsilonGrid {
+    fn to_png(&self, filename: &str) {
+        // Octagons and squares share sides of the same length, which fixes how far apart
+        // their centres are
+        const SIDE: f64 = 12.0;
+        let half_side = SIDE / 2.0;
+        let half_octagon = SIDE * (1.0 + 2f64.sqrt()) / 2.0;
+        let pitch = half_octagon + half_side;
+        let margin = PADDING + half_octagon - pitch / 2.0;
+        let width = pitch * self.columns as f64 + 2.0 * margin;
+        let height = pitch * self.rows as f64 + 2.0 * margin;
+        let surface = ImageSurface::create(Format::ARgb32, width as i32, height as i32)
+            .expect("Could not generate ImageSurface");
+        let context = Context::new(&surface);
+        context.set_source_rgb(1.0, 1.0, 1.0);
+        context.paint();
+
+        for coord in self.cells() {
+            let centre_x = margin + (coord.column() as f64 + 0.5) * pitch;
+            let centre_y = margin + (coord.row() as f64 + 0.5) * pitch;
+            let (a, b) = (half_side, half_octagon);
+            // Each side runs from its corner to the next one, clockwise from the north west
+            let sides: Vec<((f64, f64), Option<Coord>)> = if self.is_octagon(&coord) {
+                vec![
+                    ((-b, -a), self.northwest(&coord)),
+                    ((-a, -b), self.north(&coord)),
+                    ((a, -b), self.northeast(&coord)),
+                    ((b, -a), self.east(&coord)),
+                    ((b, a), self.southeast(&coord)),
+                    ((a, b), self.south(&coord)),
+                    ((-a, b), self.southwest(&coord)),
+                    ((-b, a), self.west(&coord)),
+                ]
+            } else {
+                vec![
+                    ((-a, -a), self.north(&coord)),
+                    ((a, -a), self.east(&coord)),
+                    ((a, a), self.south(&coord)),
+                    ((-a, a), self.west(&coord)),
+                ]
+            };
+            let corners: Vec<(f64, f64)> = sides
+                .iter()
+                .map(|((x, y), _)| (centre_x + x, centre_y + y))
+                .collect();
+            if let Some((red, green, blue)) = heat(&self.distances, &coord) {
+                context.set_source_rgb(red, green, blue);
+                context.move_to(corners[0].0, corners[0].1);
+                for corner in corners.iter().skip(1) {
+                    context.line_to(corner.0, corner.1);
+                }
+                context.close_path();
+                context.fill();
+            }
+
+            context.set_source_rgb(0.0, 0.0, 0.0);
+            for (side, (_, neighbour)) in sides.iter().enumerate() {
+                let linked = neighbour
+                    .as_ref()
+                    .is_some_and(|neighbour| self.is_linked(&coord, neighbour));
+                if !linked {
+                    let (from, to) = (corners[side], corners[(side + 1) % corners.len()]);
+                    context.move_to(from.0, from.1);
+                    context.line_to(to.0, to.1);
+                    context.stroke();
+                }
+            }
+        }
+        write_png(&surface, filename);
+    }
+}
+
+// Passages are drawn as corridors inset from the cell edges, which leaves room at the sides
+// of a crossing to show the tunnel ducking under it
+impl Renderable for WeaveGrid {
+    fn to_png(&self, filename: &str) {
+        const SIZE: f64 = 30.0;
+        const INSET: f64 = 6.0;
+        let width = SIZE * self.columns as f64 + 2.0 * PADDING;
+        let height = SIZE * self.rows as f64 + 2.0 * PADDING;
+        let surface = ImageSurface::create(Format::ARgb32, width as i32, height as i32)
+            .expect("Could not generate ImageSurface");
+        let context = Context::new(&surface);
+        context.set_source_rgb(1.0, 1.0, 1.0);
+        context.paint();
+
+        for coord in self.cells() {
+            let over = if self.is_under(&coord) {
+                self.above(&coord)
+            } else {
+                coord.clone()
+            };
+            let x1 = PADDING + over.column() as f64 * SIZE;
+            let y1 = PADDING + over.row() as f64 * SIZE;
+            let (x2, y2) = (x1 + INSET, y1 + INSET);
+            let (x3, y3) = (x1 + SIZE - INSET, y1 + SIZE - INSET);
+            let (x4, y4) = (x1 + SIZE, y1 + SIZE);
+            // The corridor rectangle and its two long edges on each side of the cell
+            let corridor = |side: Side| match side {
+                Side::North => ((x2, y1, x3, y2), [(x2, y1, x2, y2), (x3, y1, x3, y2)]),
+                Side::South => ((x2, y3, x3, y4), [(x2, y3, x2, y4), (x3, y3, x3, y4)]),
+                Side::West => ((x1, y2, x2, y3), [(x1, y2, x2, y2), (x1, y3, x2, y3)]),
+                Side::East => ((x3, y2, x4, y3), [(x3, y2, x4, y2), (x3, y3, x4, y3)]),
+            };
+            let (open, closed): (Vec<Side>, Vec<Side>) = if self.is_under(&coord) {
+                let vertical = self
+                    .links(&coord)
+                    .iter()
+                    .all(|end| end.column() == over.column());
+                let sides = if vertical {
+                    [Side::North, Side::South]
+                } else {
+                    [Side::East, Side::West]
+                };
+                (sides.to_vec(), Vec::new())
+            } else {
+                [Side::North, Side::South, Side::East, Side::West]
+                    .iter()
+                    .partition(|side| self.opens_towards(&coord, **side))
+            };
+
+            if let Some((red, green, blue)) = heat(&self.distances, &coord) {
+                context.set_source_rgb(red, green, blue);
+                if !self.is_under(&coord) {
+                    context.rectangle(x2, y2, x3 - x2, y3 - y2);
+                }
+                for side in open.iter() {
+                    let ((left, top, right, bottom), _) = corridor(*side);
+                    context.rectangle(left, top, right - left, bottom - top);
+                }
+                context.fill();
+            }
+
+            context.set_source_rgb(0.0, 0.0, 0.0);
+            for side in open.iter() {
+                for (from_x, from_y, to_x, to_y) in corridor(*side).1.iter() {
+                    context.move_to(*from_x, *from_y);
+                    context.line_to(*to_x, *to_y);
+                }
+            }
+            for side in closed.iter() {
+                let (from_x, from_y, to_x, to_y) = match side {
+                    Side::North => (x2, y2, x3, y2),
+                    Side::South => (x2, y3, x3, y3),
+                    Side::West => (x2, y2, x2, y3),
+                    Side::East => (x3, y2, x3, y3),
+                };
+                context.move_to(from_x, from_y);
+                context.line_to(to_x, to_y);
+            }
+            context.stroke();
+        }
+        write_png(&surface, filename);
+    }
+}
+
+// Cells near the root of the distances are bright and the farthest ones are dark
+fn heat(distances: &Distances, coord: &Coord) -> Option<(f64, f64, f64)> {
+    let (_, max_distance) = distances.max();
+    if max_distance == 0 || !distances.has_cell(coord) {
+        return None;
+    }
+    let intensity =
+        (max_distance - distances.get_cell_distance(coord)) as f64 / max_distance as f64;
+    let bright = 0.5 + (0.4 * intensity);
+    Some((bright, intensity, bright))
+}
+
+fn write_png(surface: &ImageSurface, filename: &str) {
+    let mut file = File::create(filename).expect("Couldn't create an output file");
+    surface
+        .write_to_png(&mut file)
+        .expect("Couldn't write to output file");
+}
+
 fn draw_line(direction: Direction, context: &Context, row: usize, col: usize, padding: f64) {
     context.line_to(padding + (30 * col) as f64, padding + (30 * row) as f64);
     match direction {
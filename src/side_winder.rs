@@ -1,36 +1,124 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::cell::GridCell;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::cell::{GridCell, Side};
 use crate::grid::Grid;
-use rand;
 
 pub struct SideWinder {}
 
 impl SideWinder {
     pub fn on(grid: &Grid) {
-        for row in 0..grid.rows {
+        SideWinder::on_with_rng(grid, &mut rand::thread_rng());
+    }
+
+    pub fn on_with_rng<R: Rng>(grid: &Grid, rng: &mut R) {
+        SideWinder::on_biased(grid, Side::North, 0.5, rng);
+    }
+
+    // Runs are closed out by carving towards `side`, which leaves the long corridor along that side.
+    pub fn on_biased<R: Rng>(grid: &Grid, side: Side, close_probability: f64, rng: &mut R) {
+        let (lines, line_length, run_side) = match side {
+            Side::North | Side::South => (grid.rows, grid.columns, Side::East),
+            Side::East | Side::West => (grid.columns, grid.rows, Side::South),
+        };
+        for line in 0..lines {
             let mut run: Vec<Rc<RefCell<GridCell>>> = Vec::new();
-            for col in 0..grid.columns {
-                let cell = &grid.grid[row][col];
+            for position in 0..line_length {
+                let cell = match run_side {
+                    Side::East => &grid.grid[line][position],
+                    _ => &grid.grid[position][line],
+                };
                 run.push(cell.clone());
-                let at_eastern_boundary = cell.borrow().east.is_none();
-                let at_northern_boundary = cell.borrow().north.is_none();
-                let should_close_out = at_eastern_boundary
-                    || (!at_northern_boundary && rand::random::<usize>() % 2 == 0);
+                let at_run_boundary = cell.borrow().neighbour(run_side).is_none();
+                let at_closing_boundary = cell.borrow().neighbour(side).is_none();
+                let should_close_out = at_run_boundary
+                    || (!at_closing_boundary && rng.gen::<f64>() < close_probability);
                 if should_close_out {
-                    let member = run.remove(rand::random::<usize>() % run.len());
-                    if member.borrow().north.is_some() {
-                        let northern_ref =
-                            member.borrow().north.as_ref().unwrap().upgrade().unwrap();
-                        GridCell::link(&mut member.borrow_mut(), &mut northern_ref.borrow_mut());
-                        run.clear();
+                    // Masked cells can leave some members of a run without a closing neighbour
+                    let closable: Vec<_> = run
+                        .iter()
+                        .filter(|member| member.borrow().neighbour(side).is_some())
+                        .collect();
+                    if let Some(member) = closable.choose(rng) {
+                        let closing_ref =
+                            member.borrow().neighbour(side).unwrap().upgrade().unwrap();
+                        GridCell::link(&mut member.borrow_mut(), &mut closing_ref.borrow_mut());
                     }
+                    run.clear();
                 } else {
-                    let eastern_ref = cell.borrow().east.as_ref().unwrap().upgrade().unwrap();
-                    GridCell::link(&mut cell.borrow_mut(), &mut eastern_ref.borrow_mut());
+                    let run_ref = cell
+                        .borrow()
+                        .neighbour(run_side)
+                        .unwrap()
+                        .upgrade()
+                        .unwrap();
+                    GridCell::link(&mut cell.borrow_mut(), &mut run_ref.borrow_mut());
                 }
             }
         }
+        if grid.size() < grid.rows * grid.columns {
+            grid.join_regions(rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Coord;
+    use crate::topology::Topology;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const ROWS: usize = 6;
+    const COLUMNS: usize = 8;
+
+    fn carve(side: Side, close_probability: f64) -> Grid {
+        let mut grid = Grid::initialize(ROWS, COLUMNS);
+        grid.configure_cells();
+        let mut rng = StdRng::seed_from_u64(5);
+        SideWinder::on_biased(&grid, side, close_probability, &mut rng);
+        assert!(grid.is_perfect());
+        grid
+    }
+
+    fn row_is_open(grid: &Grid, row: usize) -> bool {
+        (1..COLUMNS)
+            .all(|column| grid.is_linked(&Coord::from(row, column - 1), &Coord::from(row, column)))
+    }
+
+    fn column_is_open(grid: &Grid, column: usize) -> bool {
+        (1..ROWS)
+            .all(|row| grid.is_linked(&Coord::from(row - 1, column), &Coord::from(row, column)))
+    }
+
+    #[test]
+    fn leaves_a_corridor_along_the_closing_side() {
+        let sides = [Side::North, Side::South, Side::East, Side::West];
+        for side in sides.iter() {
+            let open = |grid: &Grid| match side {
+                Side::North => row_is_open(grid, 0),
+                Side::South => row_is_open(grid, ROWS - 1),
+                Side::East => column_is_open(grid, COLUMNS - 1),
+                Side::West => column_is_open(grid, 0),
+            };
+            assert!(open(&carve(*side, 0.5)));
+
+            // Closing out every cell carves straight towards the side, and never closing out
+            // leaves each line as one long run
+            let vertical = matches!(side, Side::North | Side::South);
+            let closed = carve(*side, 1.0);
+            let never = carve(*side, 0.0);
+            if vertical {
+                assert!((0..COLUMNS).all(|column| column_is_open(&closed, column)));
+                assert!((0..ROWS).all(|row| row_is_open(&never, row)));
+            } else {
+                assert!((0..ROWS).all(|row| row_is_open(&closed, row)));
+                assert!((0..COLUMNS).all(|column| column_is_open(&never, column)));
+            }
+        }
     }
 }
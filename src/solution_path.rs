@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use crate::cell::Coord;
+use crate::grid::Grid;
+use crate::preplaced::{Fixtures, Preplaced, PreplacedError};
+
+#[derive(Debug, PartialEq)]
+pub enum PathError {
+    TooShort,
+    Revisits(Coord),
+    Fixtures(PreplacedError),
+    Shortcut { expected: usize, found: usize },
+}
+
+pub struct SolutionPath {}
+
+impl SolutionPath {
+    // Carves the path and fills in the rest of the grid with the generator. The result is a
+    // perfect maze, so the path is the only way between its two ends. Returns the length of
+    // the solution as measured by `find_distances`.
+    pub fn on<F: Fn(&Grid)>(
+        grid: &mut Grid,
+        path: &[Coord],
+        generator: F,
+    ) -> Result<usize, PathError> {
+        if path.len() < 2 {
+            return Err(PathError::TooShort);
+        }
+        let mut seen = HashSet::new();
+        if let Some(coord) = path.iter().find(|coord| !seen.insert(*coord)) {
+            return Err(PathError::Revisits(coord.clone()));
+        }
+
+        let mut fixtures = Fixtures::default();
+        fixtures.carve_path(path);
+        Preplaced::on(grid, &fixtures, generator).map_err(PathError::Fixtures)?;
+
+        let expected = path.len() - 1;
+        let found = grid
+            .find_distances(path[0].clone())
+            .get_cell_distance(&path[expected]);
+        if found != expected {
+            return Err(PathError::Shortcut { expected, found });
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hunt_and_kill::HuntAndKill;
+
+    #[test]
+    fn solution_follows_the_given_path() {
+        let mut grid = Grid::initialize(6, 6);
+        grid.configure_cells();
+        let mut spiral: Vec<Coord> = (0..6).map(|column| Coord::from(0, column)).collect();
+        spiral.extend((1..6).map(|row| Coord::from(row, 5)));
+        spiral.extend((0..5).rev().map(|column| Coord::from(5, column)));
+        spiral.extend((2..5).rev().map(|row| Coord::from(row, 0)));
+        spiral.extend((1..4).map(|column| Coord::from(2, column)));
+
+        assert_eq!(
+            Ok(21),
+            SolutionPath::on(&mut grid, &spiral, HuntAndKill::on)
+        );
+        grid.distances = grid.find_distances(spiral[0].clone());
+        let path = grid.path_to(spiral[21].clone());
+        assert_eq!(spiral.len(), path.cells.len());
+        for coord in spiral.iter() {
+            assert!(path.has_cell(coord));
+        }
+    }
+
+    #[test]
+    fn rejects_paths_that_cross_themselves() {
+        let mut grid = Grid::initialize(3, 3);
+        grid.configure_cells();
+        let path = [
+            Coord::from(0, 0),
+            Coord::from(0, 1),
+            Coord::from(1, 1),
+            Coord::from(1, 0),
+            Coord::from(0, 0),
+        ];
+        assert_eq!(
+            Err(PathError::Revisits(Coord::from(0, 0))),
+            SolutionPath::on(&mut grid, &path, HuntAndKill::on)
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+
+use crate::cell::Coord;
+use crate::disjoint_set::DisjointSet;
+use crate::grid::Grid;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    MirrorLeftRight,
+    MirrorTopBottom,
+    Rotate2,
+    Rotate4,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SymmetryError {
+    NotSquare,
+    NoPerfectJoin { loops: usize },
+}
+
+impl Symmetry {
+    pub fn images(self, grid: &Grid, coord: &Coord) -> Vec<Coord> {
+        let (row, column) = (coord.row(), coord.column());
+        let (last_row, last_column) = (grid.rows - 1, grid.columns - 1);
+        match self {
+            Symmetry::MirrorLeftRight => {
+                vec![coord.clone(), Coord::from(row, last_column - column)]
+            }
+            Symmetry::MirrorTopBottom => {
+                vec![coord.clone(), Coord::from(last_row - row, column)]
+            }
+            Symmetry::Rotate2 => vec![
+                coord.clone(),
+                Coord::from(last_row - row, last_column - column),
+            ],
+            Symmetry::Rotate4 => vec![
+                coord.clone(),
+                Coord::from(column, last_row - row),
+                Coord::from(last_row - row, last_column - column),
+                Coord::from(last_column - column, row),
+            ],
+        }
+    }
+
+    // The fundamental region never overlaps its own images, cells left out of every image form the seams
+    fn in_region(self, grid: &Grid, row: usize, column: usize) -> bool {
+        let (rows, columns) = (grid.rows, grid.columns);
+        match self {
+            Symmetry::MirrorLeftRight => column < columns / 2,
+            Symmetry::MirrorTopBottom => row < rows / 2,
+            Symmetry::Rotate2 => {
+                row < rows / 2 || (rows % 2 == 1 && row == rows / 2 && column < columns / 2)
+            }
+            Symmetry::Rotate4 => {
+                if rows % 2 == 0 {
+                    row < rows / 2 && column < columns / 2
+                } else {
+                    row < rows / 2 && column <= columns / 2
+                }
+            }
+        }
+    }
+}
+
+pub struct Symmetric {}
+
+impl Symmetric {
+    // Returns the number of loops the seams needed, which is zero for a perfect maze. A perfect
+    // symmetric maze has a cell or a passage that the symmetry maps onto itself, and Rotate2
+    // and Rotate4 have neither when both the rows and the columns are even, so on those grids
+    // they always need loops and `perfect` makes them fail.
+    pub fn on<F: Fn(&Grid)>(
+        grid: &mut Grid,
+        symmetry: Symmetry,
+        perfect: bool,
+        generator: F,
+    ) -> Result<usize, SymmetryError> {
+        if symmetry == Symmetry::Rotate4 && grid.rows != grid.columns {
+            return Err(SymmetryError::NotSquare);
+        }
+
+        let original_mask = grid.mask.clone();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                if !symmetry.in_region(grid, row, column) {
+                    grid.mask.set(row, column, false);
+                }
+            }
+        }
+        grid.configure_cells();
+        generator(grid);
+        grid.mask = original_mask;
+        grid.configure_cells();
+
+        let mut passages = Vec::new();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                if symmetry.in_region(grid, row, column) {
+                    for link in grid.grid[row][column].borrow().get_links() {
+                        passages.push((Coord::from(row, column), link));
+                    }
+                }
+            }
+        }
+        for (from, to) in passages {
+            let froms = symmetry.images(grid, &from);
+            let tos = symmetry.images(grid, &to);
+            for (from, to) in froms.iter().zip(tos.iter()) {
+                if grid.is_active(from.row(), from.column())
+                    && grid.is_active(to.row(), to.column())
+                {
+                    grid.link(from, to);
+                }
+            }
+        }
+
+        let loops = Symmetric::join_seams(grid, symmetry);
+        if perfect && loops > 0 {
+            Err(SymmetryError::NoPerfectJoin { loops })
+        } else {
+            Ok(loops)
+        }
+    }
+
+    // Adds whole orbits of passages across the seams so the maze stays symmetric. Orbits that
+    // would close a loop are held back until no loop-free orbit is left to join the rest.
+    fn join_seams(grid: &Grid, symmetry: Symmetry) -> usize {
+        let index_of = |coord: &Coord| coord.row() * grid.columns + coord.column();
+        let mut regions = DisjointSet::initialize(grid.rows * grid.columns);
+        for cell in grid.grid.iter().flatten() {
+            let cell = cell.borrow();
+            for link in &cell.links {
+                regions.union(cell.row * grid.columns + cell.column, index_of(link));
+            }
+        }
+
+        let mut orbits = Vec::new();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                let from = Coord::from(row, column);
+                let neighbours = [Coord::from(row, column + 1), Coord::from(row + 1, column)];
+                for to in neighbours.iter() {
+                    if !grid.is_active(row, column) || !grid.is_active(to.row(), to.column()) {
+                        continue;
+                    }
+                    let froms = symmetry.images(grid, &from);
+                    let tos = symmetry.images(grid, to);
+                    let mut orbit = froms
+                        .into_iter()
+                        .zip(tos)
+                        .map(|(a, b)| {
+                            if index_of(&a) < index_of(&b) {
+                                (a, b)
+                            } else {
+                                (b, a)
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    orbit.sort_by_key(|(a, b)| (index_of(a), index_of(b)));
+                    orbit.dedup();
+                    if orbit[0] == (from.clone(), to.clone()) {
+                        orbits.push(orbit);
+                    }
+                }
+            }
+        }
+        orbits.shuffle(&mut rand::thread_rng());
+
+        let mut loops = 0;
+        for allow_loops in &[false, true] {
+            if *allow_loops && components(grid, &mut regions) == 1 {
+                break;
+            }
+            for orbit in &orbits {
+                let mut merged = HashMap::new();
+                let mut merges = 0;
+                for (from, to) in orbit {
+                    let left = resolve(&merged, regions.find(index_of(from)));
+                    let right = resolve(&merged, regions.find(index_of(to)));
+                    if left != right {
+                        merged.insert(left, right);
+                        merges += 1;
+                    }
+                }
+                let closes_loop = merges < orbit.len();
+                if merges == 0 || (closes_loop && !allow_loops) {
+                    continue;
+                }
+                for (from, to) in orbit {
+                    grid.link(from, to);
+                    regions.union(index_of(from), index_of(to));
+                }
+                loops += orbit.len() - merges;
+            }
+        }
+        loops
+    }
+}
+
+fn resolve(merged: &HashMap<usize, usize>, mut region: usize) -> usize {
+    while let Some(next) = merged.get(&region) {
+        region = *next;
+    }
+    region
+}
+
+fn components(grid: &Grid, regions: &mut DisjointSet) -> usize {
+    let mut roots = (0..grid.rows * grid.columns)
+        .filter(|index| grid.is_active(index / grid.columns, index % grid.columns))
+        .map(|index| regions.find(index))
+        .collect::<Vec<_>>();
+    roots.sort_unstable();
+    roots.dedup();
+    roots.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+    use crate::topology::Topology;
+
+    const SYMMETRIES: [Symmetry; 4] = [
+        Symmetry::MirrorLeftRight,
+        Symmetry::MirrorTopBottom,
+        Symmetry::Rotate2,
+        Symmetry::Rotate4,
+    ];
+
+    fn grid(rows: usize, columns: usize) -> Grid {
+        let mut grid = Grid::initialize(rows, columns);
+        grid.configure_cells();
+        grid
+    }
+
+    fn is_symmetric(grid: &Grid, symmetry: Symmetry) -> bool {
+        grid.cells().iter().all(|from| {
+            grid.links(from).iter().all(|to| {
+                let tos = symmetry.images(grid, to);
+                symmetry
+                    .images(grid, from)
+                    .iter()
+                    .zip(tos.iter())
+                    .all(|(from, to)| grid.is_linked(from, to))
+            })
+        })
+    }
+
+    #[test]
+    fn carves_perfect_symmetric_mazes() {
+        for symmetry in SYMMETRIES.iter() {
+            for (rows, columns) in [(9, 9), (10, 10), (7, 10)].iter() {
+                let even = rows % 2 == 0 && columns % 2 == 0;
+                let square = rows == columns;
+                if (*symmetry == Symmetry::Rotate4 && !square)
+                    || (even && (*symmetry == Symmetry::Rotate2 || *symmetry == Symmetry::Rotate4))
+                {
+                    continue;
+                }
+                let mut grid = grid(*rows, *columns);
+                let outcome = Symmetric::on(&mut grid, *symmetry, true, RecursiveBacktracker::on);
+                assert_eq!(Ok(0), outcome, "{:?} {}x{}", symmetry, rows, columns);
+                assert_eq!(Ok(()), grid.validate());
+                assert!(grid.is_perfect());
+                assert!(is_symmetric(&grid, *symmetry));
+            }
+        }
+    }
+
+    #[test]
+    fn rotations_of_even_grids_need_loops() {
+        for symmetry in [Symmetry::Rotate2, Symmetry::Rotate4].iter() {
+            let outcome = Symmetric::on(&mut grid(8, 8), *symmetry, true, RecursiveBacktracker::on);
+            assert!(matches!(outcome, Err(SymmetryError::NoPerfectJoin { .. })));
+
+            let mut grid = grid(8, 8);
+            let loops =
+                Symmetric::on(&mut grid, *symmetry, false, RecursiveBacktracker::on).unwrap();
+            assert!(loops > 0);
+            assert_eq!(Ok(()), grid.validate());
+            assert!(!grid.is_perfect());
+            assert!(is_symmetric(&grid, *symmetry));
+        }
+    }
+
+    #[test]
+    fn rotate4_needs_a_square_grid() {
+        let outcome = Symmetric::on(
+            &mut grid(6, 8),
+            Symmetry::Rotate4,
+            false,
+            RecursiveBacktracker::on,
+        );
+        assert_eq!(Err(SymmetryError::NotSquare), outcome);
+    }
+}
@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use crate::cell::Coord;
+use crate::distances::Distances;
+
+// What the shape-agnostic generators need from a grid. Cells are named by `Coord`, whatever
+// the rows and columns mean for the shape, and are also numbered densely by `index` so
+// generators can keep their own per-cell state in flat vectors.
+pub trait Topology {
+    // One more than the largest index of any cell
+    fn capacity(&self) -> usize;
+    fn index(&self, coord: &Coord) -> usize;
+    fn coord(&self, index: usize) -> Coord;
+    // Every cell a maze can reach, in index order
+    fn cells(&self) -> Vec<Coord>;
+    fn neighbours(&self, coord: &Coord) -> Vec<Coord>;
+    fn links(&self, coord: &Coord) -> Vec<Coord>;
+    fn link(&self, from: &Coord, to: &Coord);
+
+    fn size(&self) -> usize {
+        self.cells().len()
+    }
+
+    fn random_cell<R: Rng>(&self, rng: &mut R) -> Coord {
+        let cells = self.cells();
+        cells[rng.gen_range(0, cells.len())].clone()
+    }
+
+    fn is_linked(&self, from: &Coord, to: &Coord) -> bool {
+        self.links(from).contains(to)
+    }
+
+    fn deadends(&self) -> usize {
+        self.cells()
+            .iter()
+            .filter(|coord| self.links(coord).len() == 1)
+            .count()
+    }
+
+    fn find_distances(&self, start: Coord) -> Distances {
+        let mut distances = Distances::initialize(start.clone());
+        let mut frontier = vec![start];
+        let mut distance = 0;
+        while !frontier.is_empty() {
+            distance += 1;
+            let mut new_frontier = Vec::new();
+            for coord in frontier {
+                for link in self.links(&coord) {
+                    if !distances.has_cell(&link) {
+                        distances.set_cell_distance(link.clone(), distance);
+                        new_frontier.push(link);
+                    }
+                }
+            }
+            frontier = new_frontier;
+        }
+        distances
+    }
+
+    // A perfect maze has exactly one route between any two cells: every cell can be reached
+    // and there is one passage fewer than there are cells
+    fn is_perfect(&self) -> bool {
+        let cells = self.cells();
+        let start = match cells.first() {
+            Some(start) => start.clone(),
+            None => return true,
+        };
+        let passages: usize = cells.iter().map(|coord| self.links(coord).len()).sum();
+        passages / 2 + 1 == cells.len() && self.find_distances(start).cells.len() == cells.len()
+    }
+}
+
+// Passages for grid shapes without a cell type of their own. Cells are laid out in rows of
+// any length, numbered row by row, so a rectangle and the rings of a polar grid both fit.
+#[derive(Debug)]
+pub struct Passages {
+    // Where each row starts, followed by the total number of cells
+    offsets: Vec<usize>,
+    links: Vec<RefCell<HashSet<Coord>>>,
+}
+
+impl Passages {
+    pub fn rectangle(rows: usize, columns: usize) -> Passages {
+        Passages::rows(&vec![columns; rows])
+    }
+
+    pub fn rows(lengths: &[usize]) -> Passages {
+        let mut offsets = vec![0];
+        for length in lengths {
+            offsets.push(offsets[offsets.len() - 1] + length);
+        }
+        let capacity = offsets[offsets.len() - 1];
+        Passages {
+            offsets,
+            links: (0..capacity)
+                .map(|_| RefCell::new(HashSet::new()))
+                .collect(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn index(&self, coord: &Coord) -> usize {
+        self.offsets[coord.row()] + coord.column()
+    }
+
+    pub fn coord(&self, index: usize) -> Coord {
+        let row = self.offsets.partition_point(|offset| *offset <= index) - 1;
+        Coord::from(row, index - self.offsets[row])
+    }
+
+    pub fn cells(&self) -> Vec<Coord> {
+        (0..self.capacity())
+            .map(|index| self.coord(index))
+            .collect()
+    }
+
+    pub fn links(&self, coord: &Coord) -> Vec<Coord> {
+        self.links[self.index(coord)]
+            .borrow()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn link(&self, from: &Coord, to: &Coord) {
+        self.links[self.index(from)].borrow_mut().insert(to.clone());
+        self.links[self.index(to)].borrow_mut().insert(from.clone());
+    }
+}
+
+// A grid shape that keeps its passages in `Passages` only has to say which cells are next
+// to each other to be a `Topology`
+pub trait Shape {
+    fn passages(&self) -> &Passages;
+    fn adjacent(&self, coord: &Coord) -> Vec<Coord>;
+}
+
+impl<T: Shape> Topology for T {
+    fn capacity(&self) -> usize {
+        self.passages().capacity()
+    }
+
+    fn index(&self, coord: &Coord) -> usize {
+        self.passages().index(coord)
+    }
+
+    fn coord(&self, index: usize) -> Coord {
+        self.passages().coord(index)
+    }
+
+    fn cells(&self) -> Vec<Coord> {
+        self.passages().cells()
+    }
+
+    fn neighbours(&self, coord: &Coord) -> Vec<Coord> {
+        self.adjacent(coord)
+    }
+
+    fn links(&self, coord: &Coord) -> Vec<Coord> {
+        self.passages().links(coord)
+    }
+
+    fn link(&self, from: &Coord, to: &Coord) {
+        self.passages().link(from, to)
+    }
+
+    fn size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polar_grid::PolarGrid;
+
+    #[test]
+    fn numbers_rows_of_any_length() {
+        let passages = Passages::rows(&[1, 6, 12]);
+        assert_eq!(19, passages.capacity());
+        assert_eq!(7, passages.index(&Coord::from(2, 0)));
+        for index in 0..passages.capacity() {
+            assert_eq!(index, passages.index(&passages.coord(index)));
+        }
+    }
+
+    #[test]
+    fn perfect_needs_every_cell_and_no_loops() {
+        let grid = PolarGrid::initialize(2);
+        assert!(!grid.is_perfect());
+        for column in 0..6 {
+            grid.link(&Coord::from(0, 0), &Coord::from(1, column));
+        }
+        assert!(grid.is_perfect());
+        grid.link(&Coord::from(1, 0), &Coord::from(1, 1));
+        assert!(!grid.is_perfect());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::cell::Coord;
+use crate::distances::Distances;
+use crate::topology::{Passages, Shape};
+
+// A grid of triangles pointing alternately up and down along each row. A cell whose row and
+// column add up to an even number points up, so it shares its base with the cell below it;
+// the others point down and share their base with the cell above.
+#[derive(Debug)]
+pub struct TriangleGrid {
+    pub rows: usize,
+    pub columns: usize,
+    passages: Passages,
+    pub distances: Distances,
+}
+
+impl TriangleGrid {
+    pub fn initialize(rows: usize, columns: usize) -> TriangleGrid {
+        TriangleGrid {
+            rows,
+            columns,
+            passages: Passages::rectangle(rows, columns),
+            distances: Distances::initialize(Coord::from(0, 0)),
+        }
+    }
+
+    pub fn is_upright(&self, coord: &Coord) -> bool {
+        (coord.row() + coord.column()).is_multiple_of(2)
+    }
+
+    pub fn west(&self, coord: &Coord) -> Option<Coord> {
+        if coord.column() == 0 {
+            return None;
+        }
+        Some(Coord::from(coord.row(), coord.column() - 1))
+    }
+
+    pub fn east(&self, coord: &Coord) -> Option<Coord> {
+        if coord.column() + 1 >= self.columns {
+            return None;
+        }
+        Some(Coord::from(coord.row(), coord.column() + 1))
+    }
+
+    // The cell on the other side of the base, below an upright cell or above an inverted one
+    pub fn base(&self, coord: &Coord) -> Option<Coord> {
+        if self.is_upright(coord) {
+            if coord.row() + 1 >= self.rows {
+                return None;
+            }
+            Some(Coord::from(coord.row() + 1, coord.column()))
+        } else {
+            if coord.row() == 0 {
+                return None;
+            }
+            Some(Coord::from(coord.row() - 1, coord.column()))
+        }
+    }
+}
+
+impl Shape for TriangleGrid {
+    fn passages(&self) -> &Passages {
+        &self.passages
+    }
+
+    fn adjacent(&self, coord: &Coord) -> Vec<Coord> {
+        vec![self.west(coord), self.east(coord), self.base(coord)]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+    use crate::wilsons::Wilsons;
+
+    #[test]
+    fn picks_the_base_neighbour_by_parity() {
+        let grid = TriangleGrid::initialize(3, 4);
+        assert_eq!(
+            vec![Coord::from(1, 0), Coord::from(1, 2), Coord::from(2, 1)],
+            grid.neighbours(&Coord::from(1, 1))
+        );
+        assert_eq!(
+            vec![Coord::from(1, 1), Coord::from(1, 3), Coord::from(0, 2)],
+            grid.neighbours(&Coord::from(1, 2))
+        );
+        assert_eq!(
+            vec![Coord::from(0, 1), Coord::from(1, 0)],
+            grid.neighbours(&Coord::from(0, 0))
+        );
+    }
+
+    #[test]
+    fn carves_a_perfect_triangle_maze() {
+        let grid = TriangleGrid::initialize(6, 11);
+        Wilsons::on(&grid);
+        assert!(grid.is_perfect());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::cell::Coord;
+use crate::grid::Grid;
+use crate::topology::Topology;
+
+pub struct Unicursal {}
+
+impl Unicursal {
+    // Walks a perfect maze with one hand on the wall and keeps the walk as a labyrinth:
+    // every maze cell becomes a 2x2 block, so the result has twice the rows and columns
+    // and a single passage that visits every cell exactly once.
+    pub fn from(maze: &Grid) -> Option<Grid> {
+        if maze.validate().is_err() || !maze.is_perfect() {
+            return None;
+        }
+
+        let mut labyrinth = Grid::initialize(maze.rows * 2, maze.columns * 2);
+        labyrinth.configure_cells();
+        for row in 0..maze.rows {
+            for column in 0..maze.columns {
+                if !maze.is_active(row, column) {
+                    for (r, c) in block(row, column).iter() {
+                        labyrinth.deactivate(&Coord::from(*r, *c));
+                    }
+                }
+            }
+        }
+
+        for row in 0..maze.rows {
+            for column in 0..maze.columns {
+                if !maze.is_active(row, column) {
+                    continue;
+                }
+                let cell = maze.grid[row][column].borrow();
+                let [top_left, top_right, bottom_left, bottom_right] = block(row, column);
+                let linked = |r: usize, c: usize| cell.links.contains(&Coord::from(r, c));
+                let north = row > 0 && linked(row - 1, column);
+                let west = column > 0 && linked(row, column - 1);
+                let south = linked(row + 1, column);
+                let east = linked(row, column + 1);
+
+                if !north {
+                    link(&labyrinth, top_left, top_right);
+                }
+                if !south {
+                    link(&labyrinth, bottom_left, bottom_right);
+                }
+                if !west {
+                    link(&labyrinth, top_left, bottom_left);
+                }
+                if !east {
+                    link(&labyrinth, top_right, bottom_right);
+                }
+                if east {
+                    let [east_top_left, _, east_bottom_left, _] = block(row, column + 1);
+                    link(&labyrinth, top_right, east_top_left);
+                    link(&labyrinth, bottom_right, east_bottom_left);
+                }
+                if south {
+                    let [south_top_left, south_top_right, _, _] = block(row + 1, column);
+                    link(&labyrinth, bottom_left, south_top_left);
+                    link(&labyrinth, bottom_right, south_top_right);
+                }
+            }
+        }
+
+        // The blocks form one closed circuit; opening it at the first cell leaves a path
+        if let Some(first) = maze.mask.first_location() {
+            let [top_left, top_right, _, _] = block(first.row(), first.column());
+            labyrinth.unlink(
+                &Coord::from(top_left.0, top_left.1),
+                &Coord::from(top_right.0, top_right.1),
+            );
+        }
+        Some(labyrinth)
+    }
+}
+
+// Top left, top right, bottom left and bottom right of a maze cell at double resolution
+fn block(row: usize, column: usize) -> [(usize, usize); 4] {
+    let (r, c) = (row * 2, column * 2);
+    [(r, c), (r, c + 1), (r + 1, c), (r + 1, c + 1)]
+}
+
+fn link(grid: &Grid, from: (usize, usize), to: (usize, usize)) {
+    grid.link(&Coord::from(from.0, from.1), &Coord::from(to.0, to.1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+
+    #[test]
+    fn doubles_a_perfect_maze_into_a_single_path() {
+        let mut maze = Grid::initialize(4, 5);
+        maze.configure_cells();
+        RecursiveBacktracker::on(&maze);
+        let labyrinth = Unicursal::from(&maze).unwrap();
+        assert_eq!((labyrinth.rows, labyrinth.columns), (8, 10));
+        assert!(labyrinth.validate().is_ok());
+
+        let degrees: Vec<usize> = labyrinth
+            .grid
+            .iter()
+            .flatten()
+            .map(|cell| cell.borrow().links.len())
+            .collect();
+        assert_eq!(degrees.iter().filter(|&&d| d == 1).count(), 2);
+        assert_eq!(degrees.iter().filter(|&&d| d == 2).count(), 78);
+
+        maze.braid(1.0, &mut rand::thread_rng());
+        assert!(Unicursal::from(&maze).is_none());
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::cell::Coord;
+use crate::disjoint_set::DisjointSet;
+use crate::grid::Grid;
+
+// Every wall of a full rows x columns grid, east walls before south walls cell by cell.
+// A maze ID has bit i set when wall i is carved into a passage, so there can be at most 64.
+fn walls(rows: usize, columns: usize) -> Vec<(Coord, Coord)> {
+    let mut walls = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            if column + 1 < columns {
+                walls.push((Coord::from(row, column), Coord::from(row, column + 1)));
+            }
+            if row + 1 < rows {
+                walls.push((Coord::from(row, column), Coord::from(row + 1, column)));
+            }
+        }
+    }
+    assert!(
+        walls.len() <= 64,
+        "Maze IDs only cover grids with up to 64 walls"
+    );
+    walls
+}
+
+pub fn maze_id(grid: &Grid) -> u64 {
+    walls(grid.rows, grid.columns)
+        .iter()
+        .enumerate()
+        .filter(|(_, (from, to))| grid.grid[from.row()][from.column()].borrow().is_linked(to))
+        .fold(0, |id, (bit, _)| id | 1 << bit)
+}
+
+// Lists the ID of every perfect maze on a full grid, in increasing order
+pub fn spanning_trees(rows: usize, columns: usize) -> Vec<u64> {
+    let walls = walls(rows, columns);
+    let mut trees = Vec::new();
+    let regions = DisjointSet::initialize(rows * columns);
+    extend_tree(
+        &walls,
+        columns,
+        0,
+        rows * columns - 1,
+        0,
+        regions,
+        &mut trees,
+    );
+    trees.sort_unstable();
+    trees
+}
+
+fn extend_tree(
+    walls: &[(Coord, Coord)],
+    columns: usize,
+    next: usize,
+    missing: usize,
+    id: u64,
+    regions: DisjointSet,
+    trees: &mut Vec<u64>,
+) {
+    if missing == 0 {
+        trees.push(id);
+        return;
+    }
+    if walls.len() - next < missing {
+        return;
+    }
+    let (from, to) = &walls[next];
+    let mut carved = regions.clone();
+    if carved.union(
+        from.row() * columns + from.column(),
+        to.row() * columns + to.column(),
+    ) {
+        extend_tree(
+            walls,
+            columns,
+            next + 1,
+            missing - 1,
+            id | 1 << next,
+            carved,
+            trees,
+        );
+    }
+    extend_tree(walls, columns, next + 1, missing, id, regions, trees);
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub trees: usize,
+    pub runs: usize,
+    pub distinct: usize,
+    pub imperfect: usize,
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+}
+
+impl Report {
+    // Wilson-Hilferty approximation: roughly a standard normal score when sampling is uniform,
+    // so anything much above 3 is strong evidence of bias.
+    pub fn z_score(&self) -> f64 {
+        let k = self.degrees_of_freedom as f64;
+        let spread = 2.0 / (9.0 * k);
+        ((self.chi_square / k).powf(1.0 / 3.0) - (1.0 - spread)) / spread.sqrt()
+    }
+}
+
+pub struct Uniformity {}
+
+impl Uniformity {
+    // Runs the generator on fresh rows x columns grids, seeding run n with seed + n, and
+    // compares how often each perfect maze came up against a uniform distribution.
+    pub fn sample<F: Fn(&Grid, &mut StdRng)>(
+        rows: usize,
+        columns: usize,
+        runs: usize,
+        seed: u64,
+        generator: F,
+    ) -> Report {
+        let trees = spanning_trees(rows, columns);
+        let mut counts: HashMap<u64, usize> = trees.iter().map(|id| (*id, 0)).collect();
+        let mut imperfect = 0;
+        for run in 0..runs {
+            let mut grid = Grid::initialize(rows, columns);
+            grid.configure_cells();
+            generator(
+                &grid,
+                &mut StdRng::seed_from_u64(seed.wrapping_add(run as u64)),
+            );
+            match counts.get_mut(&maze_id(&grid)) {
+                Some(count) => *count += 1,
+                None => imperfect += 1,
+            }
+        }
+
+        let expected = (runs - imperfect) as f64 / trees.len() as f64;
+        let chi_square = counts
+            .values()
+            .map(|count| (*count as f64 - expected).powi(2) / expected)
+            .sum();
+        Report {
+            trees: trees.len(),
+            runs,
+            distinct: counts.values().filter(|count| **count > 0).count(),
+            imperfect,
+            chi_square,
+            degrees_of_freedom: trees.len() - 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_tree::BinaryTree;
+    use crate::wilsons::Wilsons;
+
+    #[test]
+    fn counts_spanning_trees_of_tiny_grids() {
+        assert_eq!(4, spanning_trees(2, 2).len());
+        assert_eq!(15, spanning_trees(2, 3).len());
+        assert_eq!(192, spanning_trees(3, 3).len());
+    }
+
+    #[test]
+    fn tells_uniform_generators_from_biased_ones() {
+        let wilsons = Uniformity::sample(3, 3, 3840, 7, Wilsons::on_with_rng);
+        assert_eq!(0, wilsons.imperfect);
+        assert_eq!(192, wilsons.distinct);
+        assert!(wilsons.z_score() < 4.0);
+
+        let binary_tree = Uniformity::sample(3, 3, 3840, 7, BinaryTree::on_with_rng);
+        assert_eq!(16, binary_tree.distinct);
+        assert!(binary_tree.z_score() > 10.0);
+    }
+}
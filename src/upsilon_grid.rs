@@ -0,0 +1,139 @@
+use crate::cell::Coord;
+use crate::distances::Distances;
+use crate::topology::{Passages, Shape};
+
+// A tiling of octagons and small squares laid out like a checkerboard. Cells whose row and
+// column add up to an even number are octagons, which touch the squares beside them and the
+// octagons diagonally beside them. The squares only touch the four octagons beside them.
+#[derive(Debug)]
+pub struct UpsilonGrid {
+    pub rows: usize,
+    pub columns: usize,
+    passages: Passages,
+    pub distances: Distances,
+}
+
+impl UpsilonGrid {
+    pub fn initialize(rows: usize, columns: usize) -> UpsilonGrid {
+        UpsilonGrid {
+            rows,
+            columns,
+            passages: Passages::rectangle(rows, columns),
+            distances: Distances::initialize(Coord::from(0, 0)),
+        }
+    }
+
+    pub fn is_octagon(&self, coord: &Coord) -> bool {
+        (coord.row() + coord.column()).is_multiple_of(2)
+    }
+
+    fn offset(&self, coord: &Coord, rows: isize, columns: isize) -> Option<Coord> {
+        let row = coord.row() as isize + rows;
+        let column = coord.column() as isize + columns;
+        if row < 0 || column < 0 || row as usize >= self.rows || column as usize >= self.columns {
+            return None;
+        }
+        Some(Coord::from(row as usize, column as usize))
+    }
+
+    fn diagonal(&self, coord: &Coord, rows: isize, columns: isize) -> Option<Coord> {
+        if !self.is_octagon(coord) {
+            return None;
+        }
+        self.offset(coord, rows, columns)
+    }
+
+    pub fn north(&self, coord: &Coord) -> Option<Coord> {
+        self.offset(coord, -1, 0)
+    }
+
+    pub fn south(&self, coord: &Coord) -> Option<Coord> {
+        self.offset(coord, 1, 0)
+    }
+
+    pub fn east(&self, coord: &Coord) -> Option<Coord> {
+        self.offset(coord, 0, 1)
+    }
+
+    pub fn west(&self, coord: &Coord) -> Option<Coord> {
+        self.offset(coord, 0, -1)
+    }
+
+    pub fn northeast(&self, coord: &Coord) -> Option<Coord> {
+        self.diagonal(coord, -1, 1)
+    }
+
+    pub fn northwest(&self, coord: &Coord) -> Option<Coord> {
+        self.diagonal(coord, -1, -1)
+    }
+
+    pub fn southeast(&self, coord: &Coord) -> Option<Coord> {
+        self.diagonal(coord, 1, 1)
+    }
+
+    pub fn southwest(&self, coord: &Coord) -> Option<Coord> {
+        self.diagonal(coord, 1, -1)
+    }
+}
+
+impl Shape for UpsilonGrid {
+    fn passages(&self) -> &Passages {
+        &self.passages
+    }
+
+    fn adjacent(&self, coord: &Coord) -> Vec<Coord> {
+        vec![
+            self.north(coord),
+            self.northeast(coord),
+            self.east(coord),
+            self.southeast(coord),
+            self.south(coord),
+            self.southwest(coord),
+            self.west(coord),
+            self.northwest(coord),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+    use crate::topology::Topology;
+
+    #[test]
+    fn octagons_reach_diagonally_and_squares_do_not() {
+        let grid = UpsilonGrid::initialize(4, 4);
+        assert_eq!(8, grid.neighbours(&Coord::from(1, 1)).len());
+        assert_eq!(
+            vec![
+                Coord::from(0, 2),
+                Coord::from(1, 3),
+                Coord::from(2, 2),
+                Coord::from(1, 1),
+            ],
+            grid.neighbours(&Coord::from(1, 2))
+        );
+        assert_eq!(3, grid.neighbours(&Coord::from(0, 0)).len());
+    }
+
+    #[test]
+    fn diagonal_moves_shorten_distances() {
+        let grid = UpsilonGrid::initialize(5, 5);
+        for step in 0..4 {
+            grid.link(&Coord::from(step, step), &Coord::from(step + 1, step + 1));
+        }
+        assert_eq!(
+            4,
+            grid.find_distances(Coord::from(0, 0))
+                .get_cell_distance(&Coord::from(4, 4))
+        );
+
+        let grid = UpsilonGrid::initialize(7, 9);
+        RecursiveBacktracker::on(&grid);
+        assert!(grid.is_perfect());
+    }
+}
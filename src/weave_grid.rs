@@ -0,0 +1,178 @@
+use crate::cell::{Coord, Side};
+use crate::distances::Distances;
+use crate::topology::{Passages, Topology};
+
+const SIDES: [Side; 4] = [Side::North, Side::South, Side::East, Side::West];
+
+// A square grid where a passage can tunnel under a cell whose own passage crosses it at right
+// angles. The tunnel is a cell of its own, the under cell, that only exists once it has been
+// dug. The under cell beneath (row, column) is named (rows + row, column).
+#[derive(Debug)]
+pub struct WeaveGrid {
+    pub rows: usize,
+    pub columns: usize,
+    passages: Passages,
+    pub distances: Distances,
+}
+
+impl WeaveGrid {
+    pub fn initialize(rows: usize, columns: usize) -> WeaveGrid {
+        WeaveGrid {
+            rows,
+            columns,
+            passages: Passages::rectangle(2 * rows, columns),
+            distances: Distances::initialize(Coord::from(0, 0)),
+        }
+    }
+
+    pub fn is_under(&self, coord: &Coord) -> bool {
+        coord.row() >= self.rows
+    }
+
+    pub fn under(&self, coord: &Coord) -> Coord {
+        Coord::from(self.rows + coord.row(), coord.column())
+    }
+
+    // The over cell an under cell runs beneath
+    pub fn above(&self, coord: &Coord) -> Coord {
+        Coord::from(coord.row() - self.rows, coord.column())
+    }
+
+    pub fn has_tunnel(&self, coord: &Coord) -> bool {
+        !self.links(&self.under(coord)).is_empty()
+    }
+
+    pub fn step(&self, coord: &Coord, side: Side) -> Option<Coord> {
+        let (row, column) = (coord.row(), coord.column());
+        match side {
+            Side::North if row > 0 => Some(Coord::from(row - 1, column)),
+            Side::South if row + 1 < self.rows => Some(Coord::from(row + 1, column)),
+            Side::East if column + 1 < self.columns => Some(Coord::from(row, column + 1)),
+            Side::West if column > 0 => Some(Coord::from(row, column - 1)),
+            _ => None,
+        }
+    }
+
+    // Whether an over cell has a passage out through its side, either straight into the next
+    // cell or down into the tunnel beneath it
+    pub fn opens_towards(&self, coord: &Coord, side: Side) -> bool {
+        self.step(coord, side).is_some_and(|neighbour| {
+            self.is_linked(coord, &neighbour) || self.is_linked(coord, &self.under(&neighbour))
+        })
+    }
+
+    // A cell can be tunnelled under when its passage runs straight across the tunnel and
+    // nothing else has been dug beneath it yet
+    fn can_tunnel_under(&self, coord: &Coord, side: Side) -> bool {
+        let (along, across) = match side {
+            Side::North | Side::South => ([Side::North, Side::South], [Side::East, Side::West]),
+            Side::East | Side::West => ([Side::East, Side::West], [Side::North, Side::South]),
+        };
+        across.iter().all(|side| self.opens_towards(coord, *side))
+            && !along.iter().any(|side| self.opens_towards(coord, *side))
+            && !self.has_tunnel(coord)
+    }
+}
+
+impl Topology for WeaveGrid {
+    fn capacity(&self) -> usize {
+        self.passages.capacity()
+    }
+
+    fn index(&self, coord: &Coord) -> usize {
+        self.passages.index(coord)
+    }
+
+    fn coord(&self, index: usize) -> Coord {
+        self.passages.coord(index)
+    }
+
+    // The over cells followed by whichever under cells have been dug
+    fn cells(&self) -> Vec<Coord> {
+        (0..self.capacity())
+            .map(|index| self.coord(index))
+            .filter(|coord| !self.is_under(coord) || !self.links(coord).is_empty())
+            .collect()
+    }
+
+    // Besides the cells beside it, an over cell can reach the cell beyond any neighbour it
+    // could tunnel under. An under cell only leads to the two ends of its tunnel.
+    fn neighbours(&self, coord: &Coord) -> Vec<Coord> {
+        if self.is_under(coord) {
+            return self.links(coord);
+        }
+        let mut neighbours = Vec::new();
+        for side in SIDES.iter() {
+            if let Some(neighbour) = self.step(coord, *side) {
+                if self.can_tunnel_under(&neighbour, *side) {
+                    neighbours.extend(self.step(&neighbour, *side));
+                }
+                neighbours.push(neighbour);
+            }
+        }
+        neighbours
+    }
+
+    fn links(&self, coord: &Coord) -> Vec<Coord> {
+        self.passages.links(coord)
+    }
+
+    // Linking two over cells that are two apart digs a tunnel under the cell between them
+    fn link(&self, from: &Coord, to: &Coord) {
+        let rows = from.row().abs_diff(to.row());
+        let columns = from.column().abs_diff(to.column());
+        let straight = (rows == 2 && columns == 0) || (rows == 0 && columns == 2);
+        if straight && !self.is_under(from) && !self.is_under(to) {
+            let between = Coord::from(
+                (from.row() + to.row()) / 2,
+                (from.column() + to.column()) / 2,
+            );
+            let tunnel = self.under(&between);
+            self.link(from, &tunnel);
+            self.link(&tunnel, to);
+            return;
+        }
+        self.passages.link(from, to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recursive_backtracker::RecursiveBacktracker;
+
+    #[test]
+    fn hops_under_a_crossing_passage() {
+        let grid = WeaveGrid::initialize(3, 3);
+        let centre = Coord::from(1, 1);
+        assert!(!grid
+            .neighbours(&Coord::from(0, 1))
+            .contains(&Coord::from(2, 1)));
+        grid.link(&Coord::from(1, 0), &centre);
+        grid.link(&centre, &Coord::from(1, 2));
+        assert!(grid
+            .neighbours(&Coord::from(0, 1))
+            .contains(&Coord::from(2, 1)));
+
+        grid.link(&Coord::from(0, 1), &Coord::from(2, 1));
+        assert!(grid.has_tunnel(&centre));
+        assert_eq!(10, grid.size());
+        assert!(grid.opens_towards(&Coord::from(0, 1), Side::South));
+        assert!(!grid
+            .neighbours(&Coord::from(1, 0))
+            .contains(&Coord::from(1, 2)));
+        assert_eq!(
+            2,
+            grid.find_distances(Coord::from(0, 1))
+                .get_cell_distance(&Coord::from(2, 1))
+        );
+    }
+
+    #[test]
+    fn backtracker_weaves_a_perfect_maze() {
+        let grid = WeaveGrid::initialize(12, 12);
+        RecursiveBacktracker::on(&grid);
+        assert!(grid.size() >= 144);
+        assert!(grid.is_perfect());
+    }
+}
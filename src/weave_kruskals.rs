@@ -0,0 +1,123 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::cell::{Coord, Side};
+use crate::disjoint_set::DisjointSet;
+use crate::topology::Topology;
+use crate::weave_grid::WeaveGrid;
+
+pub struct WeaveKruskals {}
+
+impl WeaveKruskals {
+    pub fn on(grid: &WeaveGrid, crossings: usize) -> usize {
+        WeaveKruskals::on_with_rng(grid, crossings, &mut rand::thread_rng())
+    }
+
+    // Lays down up to `crossings` crossings at random before any other passage is carved,
+    // then knocks down walls in random order wherever they separate two regions. Returns the
+    // number of crossings that fitted.
+    pub fn on_with_rng<R: Rng>(grid: &WeaveGrid, crossings: usize, rng: &mut R) -> usize {
+        let mut regions = DisjointSet::initialize(grid.rows * grid.columns);
+        let mut placed = 0;
+        if grid.rows > 2 && grid.columns > 2 {
+            for _ in 0..crossings {
+                let coord = Coord::from(
+                    rng.gen_range(1, grid.rows - 1),
+                    rng.gen_range(1, grid.columns - 1),
+                );
+                if WeaveKruskals::add_crossing(grid, &mut regions, &coord, rng) {
+                    placed += 1;
+                }
+            }
+        }
+
+        // A crossing cell already has both of its passages, so its walls stay up
+        let mut walls = Vec::new();
+        for coord in (0..grid.rows * grid.columns).map(|index| grid.coord(index)) {
+            for side in [Side::South, Side::East].iter() {
+                if let Some(neighbour) = grid.step(&coord, *side) {
+                    if !grid.has_tunnel(&coord) && !grid.has_tunnel(&neighbour) {
+                        walls.push((coord.clone(), neighbour));
+                    }
+                }
+            }
+        }
+        walls.shuffle(rng);
+        for (from, to) in walls {
+            if regions.union(grid.index(&from), grid.index(&to)) {
+                grid.link(&from, &to);
+            }
+        }
+        placed
+    }
+
+    // A crossing needs the cell and the four around it to still be in separate regions, so
+    // that neither the passage over it nor the tunnel under it can close a loop
+    fn add_crossing<R: Rng>(
+        grid: &WeaveGrid,
+        regions: &mut DisjointSet,
+        coord: &Coord,
+        rng: &mut R,
+    ) -> bool {
+        let around: Vec<Coord> = [Side::North, Side::South, Side::East, Side::West]
+            .iter()
+            .filter_map(|side| grid.step(coord, *side))
+            .collect();
+        let mut roots: Vec<usize> = around
+            .iter()
+            .chain(std::iter::once(coord))
+            .map(|cell| regions.find(grid.index(cell)))
+            .collect();
+        roots.sort_unstable();
+        roots.dedup();
+        if !grid.links(coord).is_empty() || around.len() != 4 || roots.len() != 5 {
+            return false;
+        }
+
+        let (north, south, east, west) = (&around[0], &around[1], &around[2], &around[3]);
+        let (over, under) = if rng.gen::<bool>() {
+            ((west, east), (north, south))
+        } else {
+            ((north, south), (west, east))
+        };
+        grid.link(over.0, coord);
+        grid.link(coord, over.1);
+        grid.link(under.0, under.1);
+        // The tunnel joins its two ends but not the passage overhead
+        regions.union(grid.index(over.0), grid.index(coord));
+        regions.union(grid.index(over.1), grid.index(coord));
+        regions.union(grid.index(under.0), grid.index(under.1));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn lays_crossings_into_a_perfect_maze() {
+        let grid = WeaveGrid::initialize(10, 10);
+        let placed = WeaveKruskals::on_with_rng(&grid, 40, &mut StdRng::seed_from_u64(5));
+        assert!(placed > 0);
+        assert_eq!(100 + placed, grid.size());
+        assert!(grid.is_perfect());
+    }
+
+    #[test]
+    fn crossings_keep_one_passage_over_and_one_under() {
+        for seed in 0..50 {
+            let grid = WeaveGrid::initialize(10, 10);
+            WeaveKruskals::on_with_rng(&grid, 40, &mut StdRng::seed_from_u64(seed));
+            for coord in (0..100).map(|index| grid.coord(index)) {
+                if grid.has_tunnel(&coord) {
+                    assert_eq!(2, grid.links(&coord).len());
+                    assert_eq!(2, grid.links(&grid.under(&coord)).len());
+                }
+            }
+            assert!(grid.is_perfect());
+        }
+    }
+}
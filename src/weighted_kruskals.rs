@@ -0,0 +1,71 @@
+use rand::Rng;
+
+use crate::cell::Coord;
+use crate::cost_field::{CostField, JITTER};
+use crate::disjoint_set::DisjointSet;
+use crate::grid::Grid;
+
+pub struct WeightedKruskals {}
+
+impl WeightedKruskals {
+    pub fn on(grid: &Grid, field: &CostField) {
+        WeightedKruskals::on_with_rng(grid, field, &mut rand::thread_rng());
+    }
+
+    // Considers every wall from the cheapest to the dearest and knocks it down when it
+    // separates two regions.
+    pub fn on_with_rng<R: Rng>(grid: &Grid, field: &CostField, rng: &mut R) {
+        let mut walls = Vec::new();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                let cell = grid.grid[row][column].borrow();
+                let coord = Coord::from(row, column);
+                if cell.east.is_some() {
+                    walls.push((coord.clone(), Coord::from(row, column + 1)));
+                }
+                if cell.south.is_some() {
+                    walls.push((coord, Coord::from(row + 1, column)));
+                }
+            }
+        }
+        let mut walls: Vec<_> = walls
+            .into_iter()
+            .map(|(from, to)| {
+                let cost = field.edge_cost(&from, &to) + rng.gen::<f64>() * JITTER;
+                ((cost * u32::MAX as f64) as u64, from, to)
+            })
+            .collect();
+        walls.sort_unstable_by_key(|(cost, _, _)| *cost);
+
+        let index_of = |coord: &Coord| coord.row() * grid.columns + coord.column();
+        let mut regions = DisjointSet::initialize(grid.rows * grid.columns);
+        for (_, from, to) in walls {
+            if regions.union(index_of(&from), index_of(&to)) {
+                grid.link(&from, &to);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn carves_the_cheap_corridor_first() {
+        // The corridor fills up before any dearer cell joins, so it ends up as one passage
+        let field = CostField::from_fn(9, 12, |row, _| if row == 4 { 0.0 } else { 1.0 });
+        for seed in 0..10 {
+            let mut grid = Grid::initialize(9, 12);
+            grid.configure_cells();
+            WeightedKruskals::on_with_rng(&grid, &field, &mut StdRng::seed_from_u64(seed));
+            assert!(grid.is_perfect());
+            for column in 1..12 {
+                assert!(grid.is_linked(&Coord::from(4, column - 1), &Coord::from(4, column)));
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::cell::Coord;
+use crate::cost_field::{CostField, JITTER};
+use crate::grid::Grid;
+
+pub struct WeightedPrims {}
+
+impl WeightedPrims {
+    pub fn on(grid: &Grid, field: &CostField) {
+        WeightedPrims::on_with_rng(grid, field, &mut rand::thread_rng());
+    }
+
+    // Grows the maze from a random cell by always adding the cheapest cell next to it,
+    // joined to one of its neighbours that is already in the maze.
+    pub fn on_with_rng<R: Rng>(grid: &Grid, field: &CostField, rng: &mut R) {
+        let mut in_maze = vec![vec![false; grid.columns]; grid.rows];
+        let mut frontier = BinaryHeap::new();
+        let start = grid.get_random_cell_with(rng).upgrade().unwrap();
+        let start = Coord::from(start.borrow().row, start.borrow().column);
+        add_cell(grid, field, &start, &mut in_maze, &mut frontier, rng);
+        while let Some(Reverse((_, index))) = frontier.pop() {
+            let coord = Coord::from(index / grid.columns, index % grid.columns);
+            if in_maze[coord.row()][coord.column()] {
+                continue;
+            }
+            let joins: Vec<Coord> = neighbours(grid, &coord)
+                .into_iter()
+                .filter(|neighbour| in_maze[neighbour.row()][neighbour.column()])
+                .collect();
+            grid.link(&coord, joins.choose(rng).unwrap());
+            add_cell(grid, field, &coord, &mut in_maze, &mut frontier, rng);
+        }
+    }
+}
+
+fn neighbours(grid: &Grid, coord: &Coord) -> Vec<Coord> {
+    grid.grid[coord.row()][coord.column()]
+        .borrow()
+        .neighbours()
+        .into_iter()
+        .map(|weak_ref| {
+            let neighbour = weak_ref.upgrade().unwrap();
+            let neighbour = neighbour.borrow();
+            Coord::from(neighbour.row, neighbour.column)
+        })
+        .collect()
+}
+
+// Frontier cells are kept cheapest first as (cost, row-major index)
+fn add_cell<R: Rng>(
+    grid: &Grid,
+    field: &CostField,
+    coord: &Coord,
+    in_maze: &mut [Vec<bool>],
+    frontier: &mut BinaryHeap<Reverse<(u64, usize)>>,
+    rng: &mut R,
+) {
+    in_maze[coord.row()][coord.column()] = true;
+    for neighbour in neighbours(grid, coord) {
+        if !in_maze[neighbour.row()][neighbour.column()] {
+            let cost = field.cost(neighbour.row(), neighbour.column()) + rng.gen::<f64>() * JITTER;
+            frontier.push(Reverse((
+                (cost * u32::MAX as f64) as u64,
+                neighbour.row() * grid.columns + neighbour.column(),
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn carves_the_cheap_corridor_first() {
+        // The corridor fills up before any dearer cell joins, so it ends up as one passage
+        let field = CostField::from_fn(9, 12, |row, _| if row == 4 { 0.0 } else { 1.0 });
+        for seed in 0..10 {
+            let mut grid = Grid::initialize(9, 12);
+            grid.configure_cells();
+            WeightedPrims::on_with_rng(&grid, &field, &mut StdRng::seed_from_u64(seed));
+            assert!(grid.is_perfect());
+            for column in 1..12 {
+                assert!(grid.is_linked(&Coord::from(4, column - 1), &Coord::from(4, column)));
+            }
+        }
+    }
+}
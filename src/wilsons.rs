@@ -1,64 +1,83 @@
-use crate::cell::{Coord, GridCell};
-use crate::grid::Grid;
-use rand;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::cell::Coord;
+use crate::topology::Topology;
 
 pub struct Wilsons {}
 
 impl Wilsons {
-    pub fn on(grid: &Grid) {
-        let mut unvisited = Vec::new();
-        for row in 0..grid.rows {
-            for column in 0..grid.columns {
-                unvisited.push(Coord::from(row, column));
-            }
+    pub fn on<T: Topology>(grid: &T) {
+        Wilsons::on_with_rng(grid, &mut rand::thread_rng());
+    }
+
+    pub fn on_with_rng<T: Topology, R: Rng>(grid: &T, rng: &mut R) {
+        let mut unvisited = grid.cells();
+        let first = rng.gen_range(0, unvisited.len());
+        unvisited.swap_remove(first);
+        Wilsons::walk_from(grid, unvisited, rng);
+    }
+
+    // Returns the number of random walk steps taken to join every unvisited cell to the maze
+    pub fn walk_from<T: Topology, R: Rng>(grid: &T, unvisited: Vec<Coord>, rng: &mut R) -> usize {
+        let neighbours = (0..grid.capacity())
+            .map(|index| {
+                grid.neighbours(&grid.coord(index))
+                    .iter()
+                    .map(|neighbour| grid.index(neighbour))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut in_maze = vec![true; grid.capacity()];
+        let mut starts = unvisited
+            .iter()
+            .map(|coord| grid.index(coord))
+            .collect::<Vec<_>>();
+        for start in &starts {
+            in_maze[*start] = false;
         }
-        let first = rand::random::<usize>() % unvisited.len();
-        unvisited.remove(first);
-        while !unvisited.is_empty() {
-            let rand_num = rand::random::<usize>() % unvisited.len();
-            let mut rand_cell_coord = unvisited.get(rand_num).cloned().unwrap();
-            let mut path = vec![rand_cell_coord.clone()];
-            while unvisited.contains(&rand_cell_coord) {
-                let cell_ref = grid
-                    .get_cell_ref(rand_cell_coord.row(), rand_cell_coord.column())
-                    .unwrap()
-                    .upgrade()
-                    .unwrap();
-                let neighbours = cell_ref.borrow().neighbours();
-                let rand_num = rand::random::<usize>() % neighbours.len();
-                rand_cell_coord = neighbours
-                    .get(rand_num)
-                    .map(|weak_ref| {
-                        let cell_rc = weak_ref.upgrade().unwrap();
-                        let coord = Coord::from(cell_rc.borrow().row, cell_rc.borrow().column);
-                        coord
-                    })
-                    .unwrap();
-                match path.iter().position(|coord| *coord == rand_cell_coord) {
-                    Some(position) => {
-                        path.split_off(position + 1);
-                    }
-                    None => path.push(rand_cell_coord.clone()),
-                }
+        starts.shuffle(rng);
+
+        // Each walk only remembers the last step it took out of a cell, which erases loops
+        let mut next = vec![0; grid.capacity()];
+        let mut steps = 0;
+        for start in starts {
+            if neighbours[start].is_empty() {
+                continue;
             }
-            for i in 0..(path.len() - 1) {
-                let left_cell = grid
-                    .get_cell_ref(path[i].row(), path[i].column())
-                    .unwrap()
-                    .upgrade()
-                    .unwrap();
-                let right_cell = grid
-                    .get_cell_ref(path[i + 1].row(), path[i + 1].column())
-                    .unwrap()
-                    .upgrade()
-                    .unwrap();
-                GridCell::link(&mut left_cell.borrow_mut(), &mut right_cell.borrow_mut());
-                let index = unvisited
-                    .iter()
-                    .position(|coord| coord == &path[i])
-                    .unwrap();
-                unvisited.remove(index);
+            let mut current = start;
+            while !in_maze[current] {
+                let choices = &neighbours[current];
+                next[current] = choices[rng.gen_range(0, choices.len())];
+                current = next[current];
+                steps += 1;
+            }
+            current = start;
+            while !in_maze[current] {
+                in_maze[current] = true;
+                grid.link(&grid.coord(current), &grid.coord(next[current]));
+                current = next[current];
             }
         }
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn carves_a_spanning_tree() {
+        for seed in 0..5 {
+            let mut grid = Grid::initialize(12, 17);
+            grid.configure_cells();
+            Wilsons::on_with_rng(&grid, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(Ok(()), grid.validate());
+            assert!(grid.is_perfect());
+        }
     }
 }